@@ -0,0 +1,37 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use range_header::ByteRange;
+
+#[path = "../tests/oracle/mod.rs"]
+mod oracle;
+
+fn parse(c: &mut Criterion) {
+    let many = format!(
+        "bytes={}",
+        (0..50)
+            .map(|x| format!("{}-{}", x * 100, x * 100 + 49))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let headers = [
+        ("open_ended", "bytes=1048576-"),
+        ("multi", "bytes=0-99,200-299,-100"),
+        ("many", many.as_str()),
+    ];
+
+    for (name, header) in headers.iter() {
+        let mut group = c.benchmark_group(*name);
+        group.bench_function("hand_written", |b| {
+            b.iter(|| ByteRange::try_parse(black_box(header)))
+        });
+        group.bench_function("hand_written_iter", |b| {
+            b.iter(|| ByteRange::parse_iter(black_box(header)).count())
+        });
+        group.bench_function("pest", |b| {
+            b.iter(|| oracle::byte_ranges(black_box(header)))
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
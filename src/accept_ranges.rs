@@ -0,0 +1,153 @@
+//! reference: <https://tools.ietf.org/html/rfc7233#section-2.3>
+
+use crate::{parser::Cursor, RangeParseError};
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt;
+
+/// A range unit, eg. `bytes` or `items`, `bytes` is recognized case-insensitively
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub enum RangeUnit {
+    Bytes,
+    Other(String),
+}
+
+impl RangeUnit {
+    pub fn as_str(&self) -> &str {
+        match self {
+            RangeUnit::Bytes => "bytes",
+            RangeUnit::Other(x) => x,
+        }
+    }
+}
+
+impl From<&str> for RangeUnit {
+    fn from(x: &str) -> Self {
+        if x.eq_ignore_ascii_case("bytes") {
+            RangeUnit::Bytes
+        } else {
+            RangeUnit::Other(x.to_string())
+        }
+    }
+}
+
+impl fmt::Display for RangeUnit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// `Accept-Ranges` HTTP header
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub enum AcceptRanges {
+    /// `none`, no range request is supported
+    None,
+    /// eg. `bytes` or `bytes, items`
+    Units(Vec<RangeUnit>),
+}
+
+impl AcceptRanges {
+    /// `Accept-Ranges: bytes`
+    pub fn bytes() -> Self {
+        AcceptRanges::Units(vec![RangeUnit::Bytes])
+    }
+
+    /// Parses Accept-Ranges HTTP header string as per RFC 9110, units are case-insensitive
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use range_header::{AcceptRanges, RangeUnit};
+    /// assert_eq!(AcceptRanges::parse("bytes"), Ok(AcceptRanges::bytes()));
+    /// assert_eq!(AcceptRanges::parse("Bytes"), Ok(AcceptRanges::bytes()));
+    /// assert_eq!(AcceptRanges::parse("none"), Ok(AcceptRanges::None));
+    /// assert_eq!(AcceptRanges::parse("NONE"), Ok(AcceptRanges::None));
+    /// assert_eq!(
+    ///     AcceptRanges::parse("bytes, items"),
+    ///     Ok(AcceptRanges::Units(vec![
+    ///         RangeUnit::Bytes,
+    ///         RangeUnit::Other("items".to_string()),
+    ///     ]))
+    /// );
+    /// assert_eq!(
+    ///     AcceptRanges::parse("bytes,\titems"),
+    ///     Ok(AcceptRanges::Units(vec![
+    ///         RangeUnit::Bytes,
+    ///         RangeUnit::Other("items".to_string()),
+    ///     ]))
+    /// );
+    /// assert!(AcceptRanges::parse("bytes=").is_err());
+    /// ```
+    pub fn parse(header: &str) -> Result<Self, RangeParseError> {
+        Self::parse_bytes(header.as_bytes())
+    }
+
+    /// Like [`AcceptRanges::parse`], for header values that may not be UTF-8
+    pub fn parse_bytes(header: &[u8]) -> Result<Self, RangeParseError> {
+        let mut cursor = Cursor::new(header);
+        cursor.skip_ows();
+        while cursor.eat(b',') {
+            cursor.skip_ows();
+        }
+
+        let mut units = Vec::new();
+        units.push(RangeUnit::from(
+            cursor.token().ok_or_else(|| cursor.syntax_error())?,
+        ));
+        loop {
+            cursor.skip_ows();
+            if cursor.is_end() {
+                break;
+            }
+            cursor.expect(b',')?;
+            cursor.skip_ows();
+            if let Some(unit) = cursor.token() {
+                units.push(RangeUnit::from(unit));
+            }
+        }
+
+        if units.len() == 1 && units[0].as_str().eq_ignore_ascii_case("none") {
+            return Ok(AcceptRanges::None);
+        }
+        Ok(AcceptRanges::Units(units))
+    }
+
+    /// Whether byte range requests are supported
+    pub fn accepts_bytes(&self) -> bool {
+        match self {
+            AcceptRanges::None => false,
+            AcceptRanges::Units(units) => units.contains(&RangeUnit::Bytes),
+        }
+    }
+}
+
+/// # Examples
+///
+/// ```rust
+/// use range_header::{AcceptRanges, RangeUnit};
+/// assert_eq!(AcceptRanges::bytes().to_string(), "bytes");
+/// assert_eq!(AcceptRanges::None.to_string(), "none");
+/// assert_eq!(
+///     AcceptRanges::Units(vec![RangeUnit::Bytes, RangeUnit::from("items")]).to_string(),
+///     "bytes, items"
+/// );
+/// ```
+impl fmt::Display for AcceptRanges {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AcceptRanges::None => f.write_str("none"),
+            AcceptRanges::Units(units) => {
+                for (index, unit) in units.iter().enumerate() {
+                    if index != 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", unit)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,240 @@
+//! actix-web extractor and `200`/`206`/`416` responses from in-memory or seekable bodies
+
+use crate::{
+    reader::CHUNK_SIZE, ParseConfig, RangeReader, RangeRequest, RangeResponse, Ranged,
+    Representation, Seekable,
+};
+use actix_web::{
+    body::{BodySize, MessageBody},
+    dev::Payload,
+    http::{
+        header::{HeaderValue, IF_RANGE, RANGE},
+        Method, StatusCode,
+    },
+    rt::task::{spawn_blocking, JoinHandle},
+    FromRequest, HttpRequest, HttpResponse, Responder,
+};
+use alloc::{vec, vec::Vec};
+use bytes::Bytes;
+use core::{
+    convert::{Infallible, TryFrom},
+    future::{ready, Future, Ready},
+    mem,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::io::{self, Read, Seek};
+
+/// Extracts the `Range` and `If-Range` headers of `GET` requests, see
+/// [`RangeRequest::parse_bytes`]. Specs of several `Range` fields are joined into one set,
+/// other methods always get the full representation.
+///
+/// # Examples
+///
+/// ```rust
+/// use actix_web::{http::Method, test::TestRequest, FromRequest};
+/// use range_header::RangeRequest;
+/// async fn download(request: RangeRequest) -> String {
+///     match request.ranges {
+///         Some(ranges) => format!("{} ranges requested", ranges.len()),
+///         None => "everything requested".to_string(),
+///     }
+/// }
+///
+/// let extract = |method| {
+///     let request = TestRequest::default()
+///         .method(method)
+///         .insert_header(("range", "bytes=0-1"))
+///         .to_http_request();
+///     futures_executor::block_on(RangeRequest::extract(&request)).unwrap()
+/// };
+/// assert!(extract(Method::GET).ranges.is_some());
+/// assert_eq!(extract(Method::PUT), RangeRequest::default());
+/// ```
+impl FromRequest for RangeRequest {
+    type Error = Infallible;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        if req.method() != Method::GET {
+            return ready(Ok(RangeRequest::default()));
+        }
+        let headers = req.headers();
+        ready(Ok(RangeRequest::from_fields(
+            headers.get_all(RANGE).map(HeaderValue::as_bytes),
+            headers.get(IF_RANGE).map(HeaderValue::as_bytes),
+            ParseConfig::strict(),
+        )))
+    }
+}
+
+/// Responds from an in-memory body
+///
+/// # Examples
+///
+/// ```rust
+/// use actix_web::{body::to_bytes, test::TestRequest, Responder};
+/// use range_header::{ByteRange, RangeRequest, Ranged};
+/// let request = RangeRequest::from(Some(vec![ByteRange::Last(5)]));
+/// let response = Ranged::new(request, "hello world")
+///     .respond_to(&TestRequest::default().to_http_request());
+/// assert_eq!(response.status(), 206);
+/// assert_eq!(response.headers().get("content-range").unwrap(), "bytes 6-10/11");
+/// let body = futures_executor::block_on(to_bytes(response.into_body())).unwrap();
+/// assert_eq!(body, "world");
+/// ```
+impl<B: Into<Bytes>> Responder for Ranged<B> {
+    type Body = actix_web::body::BoxBody;
+
+    fn respond_to(self, _req: &HttpRequest) -> HttpResponse<Self::Body> {
+        let response = self.response();
+        let body = self.body.into();
+        let body = match &response {
+            RangeResponse::Full { .. } => body,
+            RangeResponse::Single { range, .. } => {
+                body.slice(range.start as usize..=range.end_inclusive as usize)
+            }
+            RangeResponse::Multipart(x) => Bytes::from(x.render(&body)),
+            RangeResponse::NotSatisfiable(_) => Bytes::new(),
+        };
+        respond(&response, &self.representation, body)
+    }
+}
+
+/// Responds from a seekable body, read in chunks of up to 64 KiB while the response is sent.
+/// Reads run on the blocking thread pool of actix, so a slow source does not stall the worker.
+///
+/// # Examples
+///
+/// ```rust
+/// use actix_web::{body::to_bytes, rt::System, test::TestRequest, Responder};
+/// use range_header::{RangeRequest, Ranged};
+/// use std::io::Cursor;
+/// let request = RangeRequest::parse_bytes(Some(b"bytes=6-"), None);
+/// let ranged = Ranged::seekable(request, Cursor::new("hello world")).unwrap();
+/// let response = ranged.respond_to(&TestRequest::default().to_http_request());
+/// assert_eq!(response.status(), 206);
+/// assert_eq!(response.headers().get("content-range").unwrap(), "bytes 6-10/11");
+/// let body = System::new().block_on(to_bytes(response.into_body())).unwrap();
+/// assert_eq!(body, "world");
+/// ```
+impl<R: Read + Seek + Send + Unpin + 'static> Responder for Ranged<Seekable<R>> {
+    type Body = actix_web::body::BoxBody;
+
+    fn respond_to(self, _req: &HttpRequest) -> HttpResponse<Self::Body> {
+        let response = self.response();
+        let reader = RangeReader::from_response(self.body.0, &response);
+        respond(&response, &self.representation, BlockingBody::new(reader))
+    }
+}
+
+fn respond<B: MessageBody + 'static>(
+    response: &RangeResponse,
+    representation: &Representation,
+    body: B,
+) -> HttpResponse {
+    let status = StatusCode::from_u16(response.status()).expect("a valid status code");
+    let mut builder = HttpResponse::build(status);
+    for (name, value) in response.header_fields(representation) {
+        // the length of a sized body is set by actix-web
+        if name == "content-length" {
+            continue;
+        }
+        if let Ok(value) = HeaderValue::try_from(value) {
+            builder.insert_header((name, value));
+        }
+    }
+    builder.body(body)
+}
+
+/// Body read from a [`RangeReader`] in chunks of up to 64 KiB, one chunk at a time on the
+/// blocking thread pool
+struct BlockingBody<R> {
+    remaining: u64,
+    state: BlockingState<R>,
+}
+
+enum BlockingState<R> {
+    Idle(RangeReader<R>),
+    Reading(JoinHandle<(RangeReader<R>, io::Result<Vec<u8>>)>),
+    Failed,
+}
+
+impl<R> BlockingBody<R> {
+    fn new(reader: RangeReader<R>) -> Self {
+        BlockingBody {
+            remaining: reader.remaining(),
+            state: BlockingState::Idle(reader),
+        }
+    }
+}
+
+impl<R: Read + Seek + Send + Unpin + 'static> MessageBody for BlockingBody<R> {
+    type Error = io::Error;
+
+    fn size(&self) -> BodySize {
+        BodySize::Sized(self.remaining)
+    }
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        let this = self.get_mut();
+        if this.remaining == 0 {
+            return Poll::Ready(None);
+        }
+        loop {
+            let handle = match &mut this.state {
+                BlockingState::Idle(_) => {
+                    let mut reader = match mem::replace(&mut this.state, BlockingState::Failed) {
+                        BlockingState::Idle(x) => x,
+                        _ => unreachable!(),
+                    };
+                    let len = this.remaining.min(CHUNK_SIZE as u64) as usize;
+                    this.state = BlockingState::Reading(spawn_blocking(move || {
+                        let result = read_chunk(&mut reader, len);
+                        (reader, result)
+                    }));
+                    continue;
+                }
+                BlockingState::Reading(x) => x,
+                BlockingState::Failed => return Poll::Ready(None),
+            };
+
+            let (reader, result) = match Pin::new(handle).poll(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Ok(x)) => x,
+                Poll::Ready(Err(e)) => {
+                    this.state = BlockingState::Failed;
+                    return Poll::Ready(Some(Err(io::Error::other(e))));
+                }
+            };
+            return Poll::Ready(Some(match result {
+                Ok(chunk) => {
+                    this.remaining -= chunk.len() as u64;
+                    this.state = BlockingState::Idle(reader);
+                    Ok(Bytes::from(chunk))
+                }
+                Err(e) => {
+                    this.state = BlockingState::Failed;
+                    Err(e)
+                }
+            }));
+        }
+    }
+}
+
+fn read_chunk<R: Read + Seek>(reader: &mut RangeReader<R>, len: usize) -> io::Result<Vec<u8>> {
+    let mut chunk = vec![0; len];
+    loop {
+        match reader.read(&mut chunk) {
+            Ok(read) => {
+                chunk.truncate(read);
+                return Ok(chunk);
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
@@ -0,0 +1,113 @@
+//! Reading the body of a ranged response from an async seekable representation
+
+use crate::serve::Segment;
+use alloc::{collections::VecDeque, vec, vec::Vec};
+use bytes::Bytes;
+use core::{
+    mem,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::io::{self, SeekFrom};
+use tokio::io::{AsyncRead, AsyncSeek, ReadBuf};
+
+/// Reads the segments of a response body in chunks, seeking `inner` to the start of every range
+#[derive(Debug)]
+pub(crate) struct AsyncRangeReader<R> {
+    inner: R,
+    segments: VecDeque<Segment>,
+    /// Bytes of the front segment already read
+    offset: u64,
+    remaining: u64,
+    /// Whether a seek to the front range was started, and whether it completed
+    seek_started: bool,
+    positioned: bool,
+    buffer: Vec<u8>,
+}
+
+impl<R> AsyncRangeReader<R> {
+    pub(crate) fn new(inner: R, segments: Vec<Segment>, chunk_size: usize) -> Self {
+        AsyncRangeReader {
+            inner,
+            remaining: segments.iter().map(Segment::length).sum(),
+            segments: segments.into(),
+            offset: 0,
+            seek_started: false,
+            positioned: false,
+            buffer: vec![0; chunk_size.max(1)],
+        }
+    }
+
+    pub(crate) fn set_chunk_size(&mut self, chunk_size: usize) {
+        self.buffer = vec![0; chunk_size.max(1)];
+    }
+
+    /// Bytes left to read
+    pub(crate) fn remaining(&self) -> u64 {
+        self.remaining
+    }
+
+    fn next_segment(&mut self) {
+        self.segments.pop_front();
+        self.offset = 0;
+        self.seek_started = false;
+        self.positioned = false;
+    }
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin> AsyncRangeReader<R> {
+    /// Next chunk of the body, at most `chunk_size` bytes of a range or a whole static segment.
+    /// Fails with [`io::ErrorKind::UnexpectedEof`] if `inner` ends within a range.
+    pub(crate) fn poll_chunk(&mut self, cx: &mut Context<'_>) -> Poll<Option<io::Result<Bytes>>> {
+        loop {
+            let range = match self.segments.front_mut() {
+                None => return Poll::Ready(None),
+                Some(Segment::Static(x)) => {
+                    let data = Bytes::from(mem::take(x));
+                    self.next_segment();
+                    self.remaining -= data.len() as u64;
+                    if data.is_empty() {
+                        continue;
+                    }
+                    return Poll::Ready(Some(Ok(data)));
+                }
+                Some(Segment::Range(x)) => *x,
+            };
+            if self.offset == range.length() {
+                self.next_segment();
+                continue;
+            }
+
+            if !self.positioned {
+                if !self.seek_started {
+                    if let Err(e) =
+                        Pin::new(&mut self.inner).start_seek(SeekFrom::Start(range.start))
+                    {
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                    self.seek_started = true;
+                }
+                match Pin::new(&mut self.inner).poll_complete(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e))),
+                    Poll::Ready(Ok(_)) => self.positioned = true,
+                }
+            }
+
+            let len = (range.length() - self.offset).min(self.buffer.len() as u64) as usize;
+            let mut buf = ReadBuf::new(&mut self.buffer[..len]);
+            match Pin::new(&mut self.inner).poll_read(cx, &mut buf) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e))),
+                Poll::Ready(Ok(())) => {}
+            }
+            let data = Bytes::copy_from_slice(buf.filled());
+            if data.is_empty() {
+                return Poll::Ready(Some(Err(io::ErrorKind::UnexpectedEof.into())));
+            }
+            self.offset += data.len() as u64;
+            self.remaining -= data.len() as u64;
+            return Poll::Ready(Some(Ok(data)));
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! axum extractors and in-memory `200`/`206`/`416` responses
+
+use crate::{RangeHeader, RangeParseError, RangeRequest, RangeResponse, Ranged};
+use alloc::string::ToString;
+use axum_core::{
+    body::Body,
+    extract::{FromRequestParts, OptionalFromRequestParts},
+    response::{IntoResponse, Response},
+};
+use bytes::Bytes;
+use core::{convert::Infallible, fmt};
+use http::{request::Parts, Method, StatusCode};
+
+/// `Option<RangeHeader>` extracts the `Range` header, `None` if there is none or its unit is
+/// not `bytes`. An invalid header is rejected with `416 Range Not Satisfiable`, extract
+/// `Result<Option<RangeHeader>, RangeRejection>` to ignore it instead.
+///
+/// # Examples
+///
+/// ```rust
+/// use range_header::RangeHeader;
+/// async fn download(range: Option<RangeHeader>) -> String {
+///     match range {
+///         Some(range) => format!("{} requested", range),
+///         None => "everything requested".to_string(),
+///     }
+/// }
+/// ```
+impl<S: Send + Sync> OptionalFromRequestParts<S> for RangeHeader {
+    type Rejection = RangeRejection;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> Result<Option<Self>, Self::Rejection> {
+        match RangeHeader::from_headers(&parts.headers) {
+            Ok(x) => Ok(x),
+            Err(RangeParseError::UnsupportedUnit(_)) => Ok(None),
+            Err(e) => Err(RangeRejection(e)),
+        }
+    }
+}
+
+/// Extracts the `Range` and `If-Range` headers of `GET` requests, see
+/// [`RangeRequest::from_headers`]. Other methods always get the full representation.
+///
+/// # Examples
+///
+/// ```rust
+/// use axum_core::extract::FromRequestParts;
+/// use http::{header::RANGE, Method, Request};
+/// use range_header::RangeRequest;
+///
+/// let extract = |method| {
+///     let request = Request::builder()
+///         .method(method)
+///         .header(RANGE, "bytes=0-1")
+///         .body(())
+///         .unwrap();
+///     let (mut parts, ()) = request.into_parts();
+///     futures_executor::block_on(RangeRequest::from_request_parts(&mut parts, &()))
+///         .unwrap()
+/// };
+/// assert!(extract(Method::GET).ranges.is_some());
+/// assert_eq!(extract(Method::POST), RangeRequest::default());
+/// ```
+impl<S: Send + Sync> FromRequestParts<S> for RangeRequest {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        if parts.method != Method::GET {
+            return Ok(RangeRequest::default());
+        }
+        Ok(RangeRequest::from_headers(&parts.headers))
+    }
+}
+
+/// Rejection of an invalid `Range` header, responds `416 Range Not Satisfiable`
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct RangeRejection(pub RangeParseError);
+
+impl fmt::Display for RangeRejection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid Range header: {}", self.0)
+    }
+}
+
+impl std::error::Error for RangeRejection {}
+
+impl IntoResponse for RangeRejection {
+    fn into_response(self) -> Response {
+        (StatusCode::RANGE_NOT_SATISFIABLE, self.to_string()).into_response()
+    }
+}
+
+/// Responds from an in-memory body
+///
+/// # Examples
+///
+/// ```rust
+/// use axum_core::response::IntoResponse;
+/// use range_header::{ByteRange, RangeRequest, Ranged};
+/// async fn download(request: RangeRequest) -> Ranged<&'static str> {
+///     let mut ranged = Ranged::new(request, "hello world");
+///     ranged.representation.content_type = Some("text/plain".to_string());
+///     ranged
+/// }
+///
+/// let request = RangeRequest::from(Some(vec![ByteRange::Last(5)]));
+/// let response = Ranged::new(request, "hello world").into_response();
+/// assert_eq!(response.status(), 206);
+/// assert_eq!(response.headers()["content-range"], "bytes 6-10/11");
+///
+/// let request = RangeRequest::from(Some(vec![ByteRange::FromTo(11)]));
+/// let response = Ranged::new(request, "hello world").into_response();
+/// assert_eq!(response.status(), 416);
+/// assert_eq!(response.headers()["content-range"], "bytes */11");
+/// ```
+impl<B: Into<Bytes>> IntoResponse for Ranged<B> {
+    fn into_response(self) -> Response {
+        let response = self.response();
+        let body = self.body.into();
+        let body = match &response {
+            RangeResponse::Full { .. } => body,
+            RangeResponse::Single { range, .. } => {
+                body.slice(range.start as usize..=range.end_inclusive as usize)
+            }
+            RangeResponse::Multipart(x) => Bytes::from(x.render(&body)),
+            RangeResponse::NotSatisfiable(_) => Bytes::new(),
+        };
+
+        let mut result = Response::new(Body::from(body));
+        *result.status_mut() =
+            StatusCode::from_u16(response.status()).expect("a valid status code");
+        response.write_headers(&self.representation, result.headers_mut());
+        result
+    }
+}
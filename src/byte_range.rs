@@ -1,13 +1,14 @@
 //! reference: <https://tools.ietf.org/html/rfc7233>
 
-use pest::Parser;
-use pest_derive::Parser;
+use crate::{
+    parser::{collect_ranges, ByteRangeIter},
+    RangeParseError,
+};
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+use core::fmt;
 
-#[derive(Parser)]
-#[grammar = "./byte_range.pest"]
-struct ByteRangeParser;
-
-#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
 pub enum ByteRange {
     FromTo(u64),
     FromToAll(u64, u64),
@@ -42,43 +43,194 @@ impl ByteRange {
     ///     vec![]
     /// );
     /// ```
+    #[cfg(feature = "alloc")]
     pub fn parse(header: &str) -> Vec<Self> {
-        let byte_range_spec_iter = match ByteRangeParser::parse(Rule::byte_ranges_specifier, header)
-        {
-            Err(_) => {
-                return vec![];
-            }
-            Ok(x) => x.peek().unwrap().into_inner(),
-        };
-
         let mut result = Vec::new();
-        for spec in byte_range_spec_iter {
-            match spec.as_rule() {
-                Rule::from_to => {
-                    // eg. '200-'
-                    let offset: u64 = spec.into_inner().peek().unwrap().as_str().parse().unwrap();
-                    result.push(ByteRange::FromTo(offset));
-                }
-                Rule::from_to_all => {
-                    // eg, '200-300'
-                    let mut inner_pairs = spec.into_inner();
-                    let begin: u64 = inner_pairs.next().unwrap().as_str().parse().unwrap();
-                    let end: u64 = inner_pairs.next().unwrap().as_str().parse().unwrap();
-
-                    if begin > end {
-                        continue;
-                    }
-                    result.push(ByteRange::FromToAll(begin, end));
-                }
-                Rule::last => {
-                    // eg. '-200'
-                    let length: u64 = spec.into_inner().peek().unwrap().as_str().parse().unwrap();
-                    result.push(ByteRange::Last(length));
-                }
-                Rule::EOI => {}
-                _ => unreachable!(),
+        for spec in Self::parse_iter(header) {
+            match spec {
+                Ok(x) => result.push(x),
+                Err(RangeParseError::InvertedRange { .. }) => continue,
+                Err(_) => return vec![],
             }
         }
         result
     }
+
+    /// Like [`ByteRange::parse`], but reports why the header was rejected instead of
+    /// returning an empty vector, never panics on hostile input.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use range_header::{ByteRange, RangeParseError};
+    /// assert_eq!(
+    ///     ByteRange::try_parse("bytes=10-100, -50"),
+    ///     Ok(vec![ByteRange::FromToAll(10, 100), ByteRange::Last(50)])
+    /// );
+    ///
+    /// assert_eq!(
+    ///     ByteRange::try_parse("items=0-24"),
+    ///     Err(RangeParseError::UnsupportedUnit("items".to_string()))
+    /// );
+    ///
+    /// assert_eq!(
+    ///     ByteRange::try_parse("bytes=10-x"),
+    ///     Err(RangeParseError::Syntax { offset: 9 })
+    /// );
+    ///
+    /// assert_eq!(
+    ///     ByteRange::try_parse("bytes=99999999999999999999-,0-99999999999999999999"),
+    ///     Ok(vec![ByteRange::FromTo(u64::MAX), ByteRange::FromToAll(0, u64::MAX)])
+    /// );
+    ///
+    /// assert_eq!(
+    ///     ByteRange::try_parse("bytes=100-10"),
+    ///     Err(RangeParseError::InvertedRange { first: 100, last: 10 })
+    /// );
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn try_parse(header: &str) -> Result<Vec<Self>, RangeParseError> {
+        Self::try_parse_bytes(header.as_bytes())
+    }
+
+    /// Like [`ByteRange::try_parse`], for header values that may not be UTF-8
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use range_header::{ByteRange, RangeParseError};
+    /// assert_eq!(
+    ///     ByteRange::try_parse_bytes(b"bytes=0-99"),
+    ///     Ok(vec![ByteRange::FromToAll(0, 99)])
+    /// );
+    /// assert_eq!(
+    ///     ByteRange::try_parse_bytes(b"bytes=0-\xff"),
+    ///     Err(RangeParseError::Syntax { offset: 8 })
+    /// );
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn try_parse_bytes(header: &[u8]) -> Result<Vec<Self>, RangeParseError> {
+        collect_vec(Self::parse_iter_bytes(header))
+    }
+
+    /// Like [`ByteRange::try_parse`], but writes the specs into `buffer` and returns the
+    /// filled part of it, for targets without an allocator.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use range_header::{ByteRange, RangeParseError};
+    /// let mut buffer = [ByteRange::Last(0); 2];
+    /// assert_eq!(
+    ///     ByteRange::try_parse_into("bytes=0-99", &mut buffer),
+    ///     Ok(&[ByteRange::FromToAll(0, 99)][..])
+    /// );
+    ///
+    /// assert_eq!(
+    ///     ByteRange::try_parse_into("bytes=0-1,2-3,4-5", &mut buffer),
+    ///     Err(RangeParseError::CapacityExceeded { capacity: 2 })
+    /// );
+    /// ```
+    pub fn try_parse_into<'a>(
+        header: &str,
+        buffer: &'a mut [ByteRange],
+    ) -> Result<&'a [ByteRange], RangeParseError> {
+        collect_buffer(Self::parse_iter(header), buffer)
+    }
+
+    /// Parses lazily without allocating, specs are yielded in request order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use range_header::{ByteRange, RangeParseError};
+    /// let mut iter = ByteRange::parse_iter("bytes=0-99,50-10,-100,x");
+    /// assert_eq!(iter.next(), Some(Ok(ByteRange::FromToAll(0, 99))));
+    /// assert_eq!(
+    ///     iter.next(),
+    ///     Some(Err(RangeParseError::InvertedRange { first: 50, last: 10 }))
+    /// );
+    /// assert_eq!(iter.next(), Some(Ok(ByteRange::Last(100))));
+    /// assert_eq!(iter.next(), Some(Err(RangeParseError::Syntax { offset: 22 })));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn parse_iter(header: &str) -> ByteRangeIter<'_> {
+        Self::parse_iter_bytes(header.as_bytes())
+    }
+
+    /// Like [`ByteRange::parse_iter`], for header values that may not be UTF-8
+    pub fn parse_iter_bytes(header: &[u8]) -> ByteRangeIter<'_> {
+        ByteRangeIter::new(header, None)
+    }
+}
+
+/// Formats a single `byte-range-spec`, without the `bytes=` prefix
+///
+/// # Examples
+///
+/// ```rust
+/// use range_header::ByteRange;
+/// assert_eq!(ByteRange::FromToAll(0, 99).to_string(), "0-99");
+/// assert_eq!(ByteRange::FromTo(200).to_string(), "200-");
+/// assert_eq!(ByteRange::Last(50).to_string(), "-50");
+/// ```
+impl fmt::Display for ByteRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ByteRange::FromTo(first) => write!(f, "{}-", first),
+            ByteRange::FromToAll(first, last) => write!(f, "{}-{}", first, last),
+            ByteRange::Last(suffix) => write!(f, "-{}", suffix),
+        }
+    }
+}
+
+/// Parses a `range-set` without the unit, eg. `0-99,200-`, offsets in errors are relative to `set`
+#[cfg(feature = "alloc")]
+pub(crate) fn parse_int_ranges(set: &str) -> Result<Vec<ByteRange>, RangeParseError> {
+    collect_vec(ByteRangeIter::without_unit(set.as_bytes()))
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn collect_vec<I>(iter: I) -> Result<Vec<ByteRange>, RangeParseError>
+where
+    I: Iterator<Item = Result<ByteRange, RangeParseError>>,
+{
+    let mut result = Vec::new();
+    collect_ranges(iter, |x| {
+        result.push(x);
+        Ok(())
+    })?;
+    Ok(result)
+}
+
+pub(crate) fn collect_buffer<I>(
+    iter: I,
+    buffer: &mut [ByteRange],
+) -> Result<&[ByteRange], RangeParseError>
+where
+    I: Iterator<Item = Result<ByteRange, RangeParseError>>,
+{
+    let capacity = buffer.len();
+    let mut len = 0;
+    collect_ranges(iter, |x| {
+        let slot = buffer
+            .get_mut(len)
+            .ok_or(RangeParseError::CapacityExceeded { capacity })?;
+        *slot = x;
+        len += 1;
+        Ok(())
+    })?;
+    Ok(&buffer[..len])
+}
+
+/// Formats a `range-set` without the unit, eg. `0-99,200-`
+#[cfg(feature = "alloc")]
+pub(crate) fn fmt_int_ranges(ranges: &[ByteRange], f: &mut fmt::Formatter) -> fmt::Result {
+    for (index, range) in ranges.iter().enumerate() {
+        if index != 0 {
+            f.write_str(",")?;
+        }
+        write!(f, "{}", range)?;
+    }
+    Ok(())
 }
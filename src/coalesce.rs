@@ -0,0 +1,121 @@
+//! reference: <https://tools.ietf.org/html/rfc7233#section-4.1>
+
+use crate::{ByteRange, ResolvedRange, Unsatisfiable};
+use alloc::{vec, vec::Vec};
+
+/// A range produced by merging overlapping or adjacent requested ranges
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct CoalescedRange {
+    pub range: ResolvedRange,
+    /// Indices of the requested ranges merged into `range`, in ascending order
+    pub sources: Vec<usize>,
+}
+
+impl ResolvedRange {
+    /// Merges overlapping and adjacent ranges into a minimal set sorted by offset. Ranges
+    /// separated by at most `max_gap` bytes are merged as well, `sources` refer to positions
+    /// in `ranges`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use range_header::{CoalescedRange, ResolvedRange};
+    /// let ranges = [
+    ///     ResolvedRange { start: 200, end_inclusive: 299 },
+    ///     ResolvedRange { start: 0, end_inclusive: 99 },
+    ///     ResolvedRange { start: 100, end_inclusive: 149 },
+    /// ];
+    /// assert_eq!(
+    ///     ResolvedRange::coalesce(&ranges, 0),
+    ///     vec![
+    ///         CoalescedRange {
+    ///             range: ResolvedRange { start: 0, end_inclusive: 149 },
+    ///             sources: vec![1, 2],
+    ///         },
+    ///         CoalescedRange {
+    ///             range: ResolvedRange { start: 200, end_inclusive: 299 },
+    ///             sources: vec![0],
+    ///         },
+    ///     ]
+    /// );
+    ///
+    /// assert_eq!(ResolvedRange::coalesce(&ranges, 50).len(), 1);
+    /// ```
+    pub fn coalesce(ranges: &[ResolvedRange], max_gap: u64) -> Vec<CoalescedRange> {
+        coalesce_indexed(ranges.iter().copied().enumerate().collect(), max_gap)
+    }
+}
+
+impl ByteRange {
+    /// Resolves a whole range set against a representation of `len` bytes, then merges it
+    /// like [`ResolvedRange::coalesce`]. `sources` refer to positions in `ranges`, so
+    /// unsatisfiable ranges never show up there.
+    /// Returns `Err` if no range is satisfiable.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use range_header::{ByteRange, CoalescedRange, ResolvedRange};
+    /// let ranges = ByteRange::parse("bytes=5000-,-100,0-99,50-199");
+    /// assert_eq!(
+    ///     ByteRange::coalesce_all(&ranges, 1000, 0),
+    ///     Ok(vec![
+    ///         CoalescedRange {
+    ///             range: ResolvedRange { start: 0, end_inclusive: 199 },
+    ///             sources: vec![2, 3],
+    ///         },
+    ///         CoalescedRange {
+    ///             range: ResolvedRange { start: 900, end_inclusive: 999 },
+    ///             sources: vec![1],
+    ///         },
+    ///     ])
+    /// );
+    /// ```
+    pub fn coalesce_all(
+        ranges: &[ByteRange],
+        len: u64,
+        max_gap: u64,
+    ) -> Result<Vec<CoalescedRange>, Unsatisfiable> {
+        let resolved: Vec<(usize, ResolvedRange)> = ranges
+            .iter()
+            .enumerate()
+            .filter_map(|(index, x)| x.resolve(len).map(|x| (index, x)))
+            .collect();
+        if resolved.is_empty() {
+            return Err(Unsatisfiable {
+                complete_length: len,
+            });
+        }
+        Ok(coalesce_indexed(resolved, max_gap))
+    }
+}
+
+fn coalesce_indexed(mut ranges: Vec<(usize, ResolvedRange)>, max_gap: u64) -> Vec<CoalescedRange> {
+    ranges.sort_by_key(|(index, range)| (range.start, *index));
+
+    let mut result: Vec<CoalescedRange> = Vec::new();
+    for (index, range) in ranges {
+        if let Some(last) = result.last_mut() {
+            // last offset that can still be merged into `last`
+            let reach = last
+                .range
+                .end_inclusive
+                .saturating_add(max_gap)
+                .saturating_add(1);
+            if range.start <= reach {
+                last.range.end_inclusive = last.range.end_inclusive.max(range.end_inclusive);
+                last.sources.push(index);
+                continue;
+            }
+        }
+        result.push(CoalescedRange {
+            range,
+            sources: vec![index],
+        });
+    }
+
+    for x in result.iter_mut() {
+        x.sources.sort_unstable();
+    }
+    result
+}
@@ -0,0 +1,161 @@
+//! reference: <https://tools.ietf.org/html/rfc7233#section-4.2>
+
+use crate::{
+    parser::{unit_error, Cursor},
+    RangeParseError, ResolvedRange, Unsatisfiable,
+};
+use core::fmt;
+
+/// `Content-Range` HTTP header, `bytes` only
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum ContentRange {
+    /// eg. `bytes 0-499/1234`, or `bytes 0-499/*` when the complete length is unknown
+    Bytes {
+        range: ResolvedRange,
+        complete_length: Option<u64>,
+    },
+    /// eg. `bytes */1234`, sent along with `416 Range Not Satisfiable`
+    Unsatisfied(u64),
+}
+
+impl ContentRange {
+    /// Parses Content-Range HTTP header string as per RFC 7233, but `bytes` only. The unit is
+    /// case-insensitive.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use range_header::{ContentRange, RangeParseError, ResolvedRange};
+    /// assert_eq!(
+    ///     ContentRange::parse("bytes 0-499/1234"),
+    ///     Ok(ContentRange::Bytes {
+    ///         range: ResolvedRange { start: 0, end_inclusive: 499 },
+    ///         complete_length: Some(1234),
+    ///     })
+    /// );
+    ///
+    /// assert_eq!(
+    ///     ContentRange::parse("bytes 0-499/*"),
+    ///     Ok(ContentRange::Bytes {
+    ///         range: ResolvedRange { start: 0, end_inclusive: 499 },
+    ///         complete_length: None,
+    ///     })
+    /// );
+    ///
+    /// assert_eq!(ContentRange::parse("Bytes */1234"), Ok(ContentRange::Unsatisfied(1234)));
+    ///
+    /// assert_eq!(
+    ///     ContentRange::parse("bytes */1234"),
+    ///     Ok(ContentRange::Unsatisfied(1234))
+    /// );
+    ///
+    /// assert_eq!(
+    ///     ContentRange::parse("bytes 0-1234/1234"),
+    ///     Err(RangeParseError::OutOfBounds { last: 1234, complete_length: 1234 })
+    /// );
+    ///
+    /// assert_eq!(
+    ///     ContentRange::parse("bytes 0-18446744073709551615/*"),
+    ///     Err(RangeParseError::Overflow { offset: 8 })
+    /// );
+    /// ```
+    pub fn parse(header: &str) -> Result<Self, RangeParseError> {
+        Self::parse_bytes(header.as_bytes())
+    }
+
+    /// Like [`ContentRange::parse`], for header values that may not be UTF-8
+    pub fn parse_bytes(header: &[u8]) -> Result<Self, RangeParseError> {
+        let mut cursor = Cursor::new(header);
+        if !cursor.eat_str_ignore_case("bytes ") {
+            return Err(unit_error(header, b' ', true, cursor.pos));
+        }
+
+        if cursor.eat_str("*/") {
+            // eg. '*/1234'
+            let complete_length = cursor.digits()?;
+            cursor.expect_end()?;
+            return Ok(ContentRange::Unsatisfied(complete_length?));
+        }
+
+        // eg. '0-499/1234' or '0-499/*'
+        let start = cursor.digits()?;
+        cursor.expect(b'-')?;
+        let end_offset = cursor.pos;
+        let end_inclusive = cursor.digits()?;
+        cursor.expect(b'/')?;
+        let complete_length = if cursor.eat(b'*') {
+            None
+        } else {
+            Some(cursor.digits()?)
+        };
+        cursor.expect_end()?;
+
+        let start = start?;
+        let end_inclusive = end_inclusive?;
+        let complete_length = complete_length.transpose()?;
+        if start > end_inclusive {
+            return Err(RangeParseError::InvertedRange {
+                first: start,
+                last: end_inclusive,
+            });
+        }
+        if let Some(complete_length) = complete_length {
+            if complete_length <= end_inclusive {
+                return Err(RangeParseError::OutOfBounds {
+                    last: end_inclusive,
+                    complete_length,
+                });
+            }
+        }
+        if end_inclusive == u64::MAX {
+            // the length of the range would not fit into u64, with a complete length it is out of
+            // bounds anyway
+            return Err(RangeParseError::Overflow { offset: end_offset });
+        }
+
+        Ok(ContentRange::Bytes {
+            range: ResolvedRange {
+                start,
+                end_inclusive,
+            },
+            complete_length,
+        })
+    }
+}
+
+impl From<Unsatisfiable> for ContentRange {
+    fn from(x: Unsatisfiable) -> Self {
+        ContentRange::Unsatisfied(x.complete_length)
+    }
+}
+
+/// # Examples
+///
+/// ```rust
+/// use range_header::{ContentRange, ResolvedRange};
+/// let content_range = ContentRange::Bytes {
+///     range: ResolvedRange { start: 0, end_inclusive: 499 },
+///     complete_length: Some(1234),
+/// };
+/// assert_eq!(content_range.to_string(), "bytes 0-499/1234");
+/// assert_eq!(ContentRange::Unsatisfied(1234).to_string(), "bytes */1234");
+/// ```
+impl fmt::Display for ContentRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ContentRange::Bytes {
+                range,
+                complete_length: Some(complete_length),
+            } => write!(
+                f,
+                "bytes {}-{}/{}",
+                range.start, range.end_inclusive, complete_length
+            ),
+            ContentRange::Bytes {
+                range,
+                complete_length: None,
+            } => write!(f, "bytes {}-{}/*", range.start, range.end_inclusive),
+            ContentRange::Unsatisfied(complete_length) => write!(f, "bytes */{}", complete_length),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::fmt;
+
+/// Reason why a `Range` or `Content-Range` header was rejected
+///
+/// Non-exhaustive, as `UnsupportedUnit` only exists with the `alloc` feature and enabling it
+/// anywhere in the dependency graph must not break exhaustive matches.
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+#[non_exhaustive]
+pub enum RangeParseError {
+    /// The range unit is something other than `bytes`, eg. `items=0-24`.
+    /// Reported as a syntax error without the `alloc` feature.
+    #[cfg(feature = "alloc")]
+    UnsupportedUnit(String),
+    /// Not a valid `byte-ranges-specifier`, `offset` is the byte offset where parsing failed
+    Syntax { offset: usize },
+    /// The `Content-Range` position starting at byte `offset` does not fit into `u64`, or is
+    /// `u64::MAX` as the last byte of a range whose length would not
+    Overflow { offset: usize },
+    /// `first-byte-pos` is greater than `last-byte-pos`, eg. `bytes=100-10`
+    InvertedRange { first: u64, last: u64 },
+    /// More specs than fit into the buffer given to `ByteRange::try_parse_into`
+    CapacityExceeded { capacity: usize },
+    /// `complete-length` of a `Content-Range` is not greater than its `last-byte-pos`
+    OutOfBounds { last: u64, complete_length: u64 },
+}
+
+impl fmt::Display for RangeParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            #[cfg(feature = "alloc")]
+            RangeParseError::UnsupportedUnit(unit) => {
+                write!(f, "unsupported range unit `{}`", unit)
+            }
+            RangeParseError::Syntax { offset } => {
+                write!(f, "invalid range syntax at offset {}", offset)
+            }
+            RangeParseError::Overflow { offset } => {
+                write!(f, "range position at offset {} overflows u64", offset)
+            }
+            RangeParseError::InvertedRange { first, last } => {
+                write!(
+                    f,
+                    "first-byte-pos {} is greater than last-byte-pos {}",
+                    first, last
+                )
+            }
+            RangeParseError::CapacityExceeded { capacity } => {
+                write!(f, "more than {} ranges", capacity)
+            }
+            RangeParseError::OutOfBounds {
+                last,
+                complete_length,
+            } => write!(
+                f,
+                "last-byte-pos {} is not less than complete-length {}",
+                last, complete_length
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RangeParseError {}
@@ -0,0 +1,260 @@
+//! Conversions from and to [`http::HeaderValue`], and reading and writing the range headers of
+//! a [`http::HeaderMap`]
+
+use crate::{
+    AcceptRanges, ContentRange, IfRange, ItemsContentRange, ParseConfig, Range, RangeHeader,
+    RangeParseError, RangeRequest, RangeResponse, Representation,
+};
+use alloc::{string::ToString, vec::Vec};
+use core::{convert::TryFrom, fmt::Display};
+use http::{
+    header::{HeaderName, InvalidHeaderValue, IF_RANGE, RANGE},
+    HeaderMap, HeaderValue,
+};
+
+impl RangeHeader {
+    /// Parses every `Range` field of `headers` into one set, in order, `None` if there is none.
+    /// Fields are parsed as per RFC 9110, see [`ParseConfig::strict`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use http::{header::RANGE, HeaderMap, HeaderValue};
+    /// use range_header::{ByteRange, RangeHeader};
+    /// let mut headers = HeaderMap::new();
+    /// assert_eq!(RangeHeader::from_headers(&headers), Ok(None));
+    ///
+    /// headers.append(RANGE, HeaderValue::from_static("bytes=0-99"));
+    /// headers.append(RANGE, HeaderValue::from_static("bytes=-50"));
+    /// assert_eq!(
+    ///     RangeHeader::from_headers(&headers),
+    ///     Ok(Some(RangeHeader(vec![ByteRange::FromToAll(0, 99), ByteRange::Last(50)])))
+    /// );
+    /// ```
+    pub fn from_headers(headers: &HeaderMap) -> Result<Option<Self>, RangeParseError> {
+        RangeHeader::parse_fields(
+            headers.get_all(RANGE).iter().map(HeaderValue::as_bytes),
+            ParseConfig::strict(),
+        )
+    }
+}
+
+impl Range {
+    /// Parses every `Range` field of `headers`, in order
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use http::{header::RANGE, HeaderMap, HeaderValue};
+    /// use range_header::{Range, RangeUnit};
+    /// let mut headers = HeaderMap::new();
+    /// headers.append(RANGE, HeaderValue::from_static("items=0-24"));
+    /// let ranges = Range::from_headers(&headers).unwrap();
+    /// assert_eq!(ranges.len(), 1);
+    /// assert_eq!(ranges[0].unit, RangeUnit::from("items"));
+    /// ```
+    pub fn from_headers(headers: &HeaderMap) -> Result<Vec<Self>, RangeParseError> {
+        headers
+            .get_all(RANGE)
+            .iter()
+            .map(|x| Range::parse_bytes(x.as_bytes()))
+            .collect()
+    }
+}
+
+impl RangeRequest {
+    /// Reads the `Range` and `If-Range` fields of `headers`, see [`RangeRequest::parse_bytes`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use http::{header::RANGE, HeaderMap, HeaderValue};
+    /// use range_header::{ByteRange, RangeRequest};
+    /// let mut headers = HeaderMap::new();
+    /// headers.insert(RANGE, HeaderValue::from_static("bytes=0-99"));
+    /// assert_eq!(
+    ///     RangeRequest::from_headers(&headers).ranges,
+    ///     Some(vec![ByteRange::FromToAll(0, 99)])
+    /// );
+    /// ```
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        Self::from_headers_with(headers, ParseConfig::strict())
+    }
+
+    /// Like [`RangeRequest::from_headers`], parsing `Range` with `config`, see
+    /// [`RangeRequest::parse_bytes_with`]
+    pub fn from_headers_with(headers: &HeaderMap, config: ParseConfig) -> Self {
+        RangeRequest::from_fields(
+            headers.get_all(RANGE).iter().map(HeaderValue::as_bytes),
+            headers.get(IF_RANGE).map(HeaderValue::as_bytes),
+            config,
+        )
+    }
+}
+
+impl RangeResponse {
+    /// Sets `Accept-Ranges`, `Content-Length` and, where they apply, `Content-Range`,
+    /// `Content-Type`, `ETag` and `Last-Modified`. Values of `representation` that are not
+    /// valid header values are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use http::HeaderMap;
+    /// use range_header::{RangePolicy, RangeRequest, RangeResponse, Representation};
+    /// let representation = Representation::new(1000);
+    /// let request = RangeRequest::parse_bytes(Some(b"bytes=0-99"), None);
+    /// let response = RangeResponse::new(&request, &representation, &RangePolicy::default());
+    ///
+    /// let mut headers = HeaderMap::new();
+    /// response.write_headers(&representation, &mut headers);
+    /// assert_eq!(headers["accept-ranges"], "bytes");
+    /// assert_eq!(headers["content-length"], "100");
+    /// assert_eq!(headers["content-range"], "bytes 0-99/1000");
+    /// ```
+    pub fn write_headers(&self, representation: &Representation, headers: &mut HeaderMap) {
+        for (name, value) in self.header_fields(representation) {
+            if let Ok(value) = HeaderValue::try_from(value) {
+                headers.insert(HeaderName::from_static(name), value);
+            }
+        }
+    }
+}
+
+/// Parses as per RFC 9110, see [`ParseConfig::strict`]
+///
+/// # Examples
+///
+/// ```rust
+/// use http::HeaderValue;
+/// use range_header::{ByteRange, RangeHeader, RangeParseError};
+/// use std::convert::TryFrom;
+/// assert_eq!(
+///     RangeHeader::try_from(&HeaderValue::from_static("bytes=0-99")),
+///     Ok(RangeHeader(vec![ByteRange::FromToAll(0, 99)]))
+/// );
+/// assert_eq!(
+///     RangeHeader::try_from(&HeaderValue::from_static("Bytes=0-1,\t-5")),
+///     Ok(RangeHeader(vec![ByteRange::FromToAll(0, 1), ByteRange::Last(5)]))
+/// );
+///
+/// let value = HeaderValue::from_bytes(b"bytes=0-\xff").unwrap();
+/// assert_eq!(
+///     RangeHeader::try_from(&value),
+///     Err(RangeParseError::Syntax { offset: 8 })
+/// );
+/// ```
+impl TryFrom<&HeaderValue> for RangeHeader {
+    type Error = RangeParseError;
+
+    fn try_from(value: &HeaderValue) -> Result<Self, Self::Error> {
+        ParseConfig::strict()
+            .try_parse_bytes(value.as_bytes())
+            .map(RangeHeader)
+    }
+}
+
+impl TryFrom<&HeaderValue> for Range {
+    type Error = RangeParseError;
+
+    fn try_from(value: &HeaderValue) -> Result<Self, Self::Error> {
+        Range::parse_bytes(value.as_bytes())
+    }
+}
+
+impl TryFrom<&HeaderValue> for ContentRange {
+    type Error = RangeParseError;
+
+    fn try_from(value: &HeaderValue) -> Result<Self, Self::Error> {
+        ContentRange::parse_bytes(value.as_bytes())
+    }
+}
+
+impl TryFrom<&HeaderValue> for AcceptRanges {
+    type Error = RangeParseError;
+
+    fn try_from(value: &HeaderValue) -> Result<Self, Self::Error> {
+        AcceptRanges::parse_bytes(value.as_bytes())
+    }
+}
+
+impl TryFrom<&HeaderValue> for IfRange {
+    type Error = RangeParseError;
+
+    fn try_from(value: &HeaderValue) -> Result<Self, Self::Error> {
+        IfRange::parse_bytes(value.as_bytes())
+    }
+}
+
+/// # Examples
+///
+/// ```rust
+/// use http::HeaderValue;
+/// use range_header::{ByteRange, RangeHeader};
+/// let header = RangeHeader(vec![ByteRange::FromTo(200)]);
+/// assert_eq!(HeaderValue::from(&header), "bytes=200-");
+/// ```
+impl From<&RangeHeader> for HeaderValue {
+    fn from(x: &RangeHeader) -> Self {
+        to_header_value(x).expect("a byte range set is ASCII only")
+    }
+}
+
+/// # Examples
+///
+/// ```rust
+/// use http::HeaderValue;
+/// use range_header::ContentRange;
+/// assert_eq!(HeaderValue::from(&ContentRange::Unsatisfied(1234)), "bytes */1234");
+/// ```
+impl From<&ContentRange> for HeaderValue {
+    fn from(x: &ContentRange) -> Self {
+        to_header_value(x).expect("a content range is ASCII only")
+    }
+}
+
+impl From<&ItemsContentRange> for HeaderValue {
+    fn from(x: &ItemsContentRange) -> Self {
+        to_header_value(x).expect("a content range is ASCII only")
+    }
+}
+
+/// Fails if a unit or an `other-range-set` contains bytes not allowed in a header
+impl TryFrom<&Range> for HeaderValue {
+    type Error = InvalidHeaderValue;
+
+    fn try_from(x: &Range) -> Result<Self, Self::Error> {
+        to_header_value(x)
+    }
+}
+
+/// Fails if a unit contains bytes not allowed in a header
+///
+/// # Examples
+///
+/// ```rust
+/// use http::HeaderValue;
+/// use range_header::AcceptRanges;
+/// use std::convert::TryFrom;
+/// assert_eq!(HeaderValue::try_from(&AcceptRanges::bytes()).unwrap(), "bytes");
+/// ```
+impl TryFrom<&AcceptRanges> for HeaderValue {
+    type Error = InvalidHeaderValue;
+
+    fn try_from(x: &AcceptRanges) -> Result<Self, Self::Error> {
+        to_header_value(x)
+    }
+}
+
+/// Fails if an entity-tag contains bytes not allowed in a header
+impl TryFrom<&IfRange> for HeaderValue {
+    type Error = InvalidHeaderValue;
+
+    fn try_from(x: &IfRange) -> Result<Self, Self::Error> {
+        to_header_value(x)
+    }
+}
+
+fn to_header_value<T: Display>(x: &T) -> Result<HeaderValue, InvalidHeaderValue> {
+    HeaderValue::try_from(x.to_string())
+}
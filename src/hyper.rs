@@ -0,0 +1,110 @@
+//! [`http_body::Body`] streaming a ranged response from an async seekable representation, for
+//! hyper and anything else built on `http-body`
+
+use crate::{async_reader::AsyncRangeReader, reader::CHUNK_SIZE, RangeResponse};
+use bytes::Bytes;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use http_body::{Body, Frame, SizeHint};
+use std::io;
+use tokio::io::{AsyncRead, AsyncSeek};
+
+/// Body of a [`RangeResponse`], read from `R` while it is sent
+///
+/// Every range is read in chunks of up to 64 KiB after seeking to its start, the boundary lines
+/// and part headers of a multipart body are sent in between. The exact length is known up
+/// front, so hyper sets `Content-Length`. The body fails with [`io::ErrorKind::UnexpectedEof`]
+/// if the source is shorter than the representation.
+///
+/// # Examples
+///
+/// ```rust
+/// use http::Response;
+/// use http_body::Body;
+/// use http_body_util::BodyExt;
+/// use range_header::{RangePolicy, RangeRequest, RangeResponse, Representation, SeekableBody};
+/// use std::io::Cursor;
+///
+/// let source = Cursor::new(b"hello world".to_vec());
+/// let representation = Representation::new(11);
+/// let request = RangeRequest::parse_bytes(Some(b"bytes=0-4,6-"), None);
+/// let range_response = RangeResponse::new(&request, &representation, &RangePolicy::default());
+///
+/// let mut response = Response::new(SeekableBody::new(source, &range_response));
+/// *response.status_mut() = http::StatusCode::from_u16(range_response.status()).unwrap();
+/// range_response.write_headers(&representation, response.headers_mut());
+/// assert_eq!(response.status(), 206);
+/// assert_eq!(
+///     response.body().size_hint().exact(),
+///     Some(range_response.content_length())
+/// );
+///
+/// let body = futures_executor::block_on(response.into_body().collect()).unwrap();
+/// let body = String::from_utf8(body.to_bytes().to_vec()).unwrap();
+/// assert!(body.contains("Content-Range: bytes 0-4/11\r\n\r\nhello\r\n"));
+/// assert!(body.contains("Content-Range: bytes 6-10/11\r\n\r\nworld\r\n"));
+/// ```
+#[derive(Debug)]
+pub struct SeekableBody<R> {
+    reader: AsyncRangeReader<R>,
+}
+
+impl<R> SeekableBody<R> {
+    /// Reads the body of `response` from `source`, the whole representation for `200 OK` and
+    /// nothing for `416 Range Not Satisfiable`
+    pub fn new(source: R, response: &RangeResponse) -> Self {
+        SeekableBody {
+            reader: AsyncRangeReader::new(source, response.segments(), CHUNK_SIZE),
+        }
+    }
+
+    /// Sets the largest chunk read from the source at once, at least one byte
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use http_body_util::BodyExt;
+    /// use range_header::{RangePolicy, RangeRequest, RangeResponse, Representation, SeekableBody};
+    /// use std::io::Cursor;
+    ///
+    /// let request = RangeRequest::parse_bytes(Some(b"bytes=6-"), None);
+    /// let response =
+    ///     RangeResponse::new(&request, &Representation::new(11), &RangePolicy::default());
+    /// let mut body = SeekableBody::new(Cursor::new(b"hello world"), &response);
+    /// body.set_chunk_size(2);
+    ///
+    /// let mut chunks = Vec::new();
+    /// while let Some(frame) = futures_executor::block_on(body.frame()) {
+    ///     chunks.push(frame.unwrap().into_data().unwrap());
+    /// }
+    /// assert_eq!(chunks, ["wo", "rl", "d"]);
+    /// ```
+    pub fn set_chunk_size(&mut self, chunk_size: usize) {
+        self.reader.set_chunk_size(chunk_size);
+    }
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin> Body for SeekableBody<R> {
+    type Data = Bytes;
+    type Error = io::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        self.get_mut()
+            .reader
+            .poll_chunk(cx)
+            .map(|x| x.map(|x| x.map(Frame::data)))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.reader.remaining() == 0
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::with_exact(self.reader.remaining())
+    }
+}
@@ -0,0 +1,203 @@
+//! reference: <https://tools.ietf.org/html/rfc7233#section-3.2>, <https://tools.ietf.org/html/rfc7232#section-2.2.2>
+
+use crate::{
+    parser::{shift_offset, Cursor},
+    RangeParseError,
+};
+use alloc::string::{String, ToString};
+use std::{
+    fmt,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// `ETag` value, `tag` is the opaque tag without quotes
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct EntityTag {
+    pub weak: bool,
+    pub tag: String,
+}
+
+impl EntityTag {
+    /// Parses an `entity-tag` as per RFC 7232
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use range_header::EntityTag;
+    /// assert_eq!(
+    ///     EntityTag::parse("W/\"xyzzy\""),
+    ///     Ok(EntityTag { weak: true, tag: "xyzzy".to_string() })
+    /// );
+    /// assert!(EntityTag::parse("xyzzy").is_err());
+    /// ```
+    pub fn parse(header: &str) -> Result<Self, RangeParseError> {
+        Self::parse_bytes(header.as_bytes())
+    }
+
+    /// Like [`EntityTag::parse`], for header values that may not be UTF-8. The opaque tag must
+    /// still be UTF-8 to fit into `tag`.
+    pub fn parse_bytes(header: &[u8]) -> Result<Self, RangeParseError> {
+        let mut cursor = Cursor::new(header);
+        let weak = cursor.eat_str("W/");
+        cursor.expect(b'"')?;
+        let start = cursor.pos;
+        while cursor.peek().is_some_and(is_etagc) {
+            cursor.pos += 1;
+        }
+        let tag = &header[start..cursor.pos];
+        cursor.expect(b'"')?;
+        cursor.expect_end()?;
+
+        let tag = core::str::from_utf8(tag).map_err(|e| RangeParseError::Syntax {
+            offset: start + e.valid_up_to(),
+        })?;
+        Ok(EntityTag {
+            weak,
+            tag: tag.to_string(),
+        })
+    }
+
+    /// Strong comparison, weak tags never match
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use range_header::EntityTag;
+    /// let strong = EntityTag::parse("\"xyzzy\"").unwrap();
+    /// let weak = EntityTag::parse("W/\"xyzzy\"").unwrap();
+    /// assert!(strong.strong_eq(&strong));
+    /// assert!(!weak.strong_eq(&weak));
+    /// assert!(!strong.strong_eq(&weak));
+    /// ```
+    pub fn strong_eq(&self, other: &EntityTag) -> bool {
+        !self.weak && !other.weak && self.tag == other.tag
+    }
+}
+
+impl fmt::Display for EntityTag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.weak {
+            f.write_str("W/")?;
+        }
+        write!(f, "\"{}\"", self.tag)
+    }
+}
+
+/// `If-Range` HTTP header
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub enum IfRange {
+    EntityTag(EntityTag),
+    Date(SystemTime),
+}
+
+impl IfRange {
+    /// Parses If-Range HTTP header string, either an `entity-tag` or an `HTTP-date`, ignoring
+    /// surrounding whitespace
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use range_header::{EntityTag, IfRange, RangeParseError};
+    /// use std::time::{Duration, UNIX_EPOCH};
+    /// assert_eq!(
+    ///     IfRange::parse("\"xyzzy\""),
+    ///     Ok(IfRange::EntityTag(EntityTag { weak: false, tag: "xyzzy".to_string() }))
+    /// );
+    ///
+    /// assert_eq!(
+    ///     IfRange::parse("Sun, 06 Nov 1994 08:49:37 GMT"),
+    ///     Ok(IfRange::Date(UNIX_EPOCH + Duration::from_secs(784111777)))
+    /// );
+    ///
+    /// assert_eq!(
+    ///     IfRange::parse(" \"xyzzy\" "),
+    ///     Ok(IfRange::EntityTag(EntityTag { weak: false, tag: "xyzzy".to_string() }))
+    /// );
+    /// assert_eq!(
+    ///     IfRange::parse("   \"a b\""),
+    ///     Err(RangeParseError::Syntax { offset: 5 })
+    /// );
+    /// assert_eq!(IfRange::parse("  yesterday"), Err(RangeParseError::Syntax { offset: 2 }));
+    /// ```
+    pub fn parse(header: &str) -> Result<Self, RangeParseError> {
+        Self::parse_bytes(header.as_bytes())
+    }
+
+    /// Like [`IfRange::parse`], for header values that may not be UTF-8
+    pub fn parse_bytes(header: &[u8]) -> Result<Self, RangeParseError> {
+        let trimmed = header.trim_ascii();
+        let leading = header.len() - header.trim_ascii_start().len();
+        if trimmed.starts_with(b"\"") || trimmed.starts_with(b"W/") {
+            return EntityTag::parse_bytes(trimmed)
+                .map(IfRange::EntityTag)
+                .map_err(|e| shift_offset(e, leading));
+        }
+        core::str::from_utf8(trimmed)
+            .ok()
+            .and_then(|x| httpdate::parse_http_date(x).ok())
+            .map(IfRange::Date)
+            .ok_or(RangeParseError::Syntax { offset: leading })
+    }
+
+    /// Whether the `Range` header applies to the current representation, if not the full
+    /// representation must be sent.
+    ///
+    /// An entity-tag only matches a strong `etag`. A date only matches a `last_modified` equal
+    /// to it, which also has to be at least one second before `now` to be a strong validator.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use range_header::{EntityTag, IfRange};
+    /// use std::time::{Duration, UNIX_EPOCH};
+    /// let etag = EntityTag::parse("\"xyzzy\"").unwrap();
+    /// assert!(IfRange::parse("\"xyzzy\"").unwrap().is_satisfied(Some(&etag), None, UNIX_EPOCH));
+    /// assert!(!IfRange::parse("W/\"xyzzy\"").unwrap().is_satisfied(Some(&etag), None, UNIX_EPOCH));
+    ///
+    /// let last_modified = UNIX_EPOCH + Duration::from_secs(784111777);
+    /// let if_range = IfRange::parse("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+    /// let now = last_modified + Duration::from_secs(3600);
+    /// assert!(if_range.is_satisfied(None, Some(last_modified), now));
+    /// assert!(!if_range.is_satisfied(None, Some(last_modified), last_modified));
+    /// ```
+    pub fn is_satisfied(
+        &self,
+        etag: Option<&EntityTag>,
+        last_modified: Option<SystemTime>,
+        now: SystemTime,
+    ) -> bool {
+        match self {
+            IfRange::EntityTag(x) => etag.is_some_and(|etag| x.strong_eq(etag)),
+            IfRange::Date(x) => match last_modified {
+                Some(last_modified) => {
+                    let last_modified = truncate_to_secs(last_modified);
+                    truncate_to_secs(*x) == last_modified
+                        && last_modified + Duration::from_secs(1) <= now
+                }
+                None => false,
+            },
+        }
+    }
+}
+
+impl fmt::Display for IfRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IfRange::EntityTag(x) => x.fmt(f),
+            IfRange::Date(x) => f.write_str(&httpdate::fmt_http_date(*x)),
+        }
+    }
+}
+
+/// `etagc`, any byte of a non-ASCII character is allowed as well
+fn is_etagc(x: u8) -> bool {
+    x == b'!' || (b'#'..=b'~').contains(&x) || x >= 0x80
+}
+
+/// HTTP dates have second precision
+fn truncate_to_secs(x: SystemTime) -> SystemTime {
+    match x.duration_since(UNIX_EPOCH) {
+        Ok(d) => UNIX_EPOCH + Duration::from_secs(d.as_secs()),
+        Err(_) => x,
+    }
+}
@@ -0,0 +1,126 @@
+//! `items` range unit used for pagination by dojo style REST APIs, eg. `Range: items=0-24`
+//! answered with `Content-Range: items 0-24/66`
+
+use crate::{
+    byte_range::fmt_int_ranges, ByteRange, IntRangeParser, RangeParseError, RangeParser, RangeSet,
+    ResolvedRange, Unsatisfiable,
+};
+use alloc::{string::ToString, vec::Vec};
+use core::fmt;
+
+/// `Range` HTTP header with `items` unit, there is no empty default as `items=` does not parse
+///
+/// # Examples
+///
+/// ```rust
+/// use range_header::{ByteRange, ItemsRange, ResolvedRange};
+/// let header = ItemsRange::parse("items=0-24").unwrap();
+/// assert_eq!(header, ItemsRange(vec![ByteRange::FromToAll(0, 24)]));
+/// assert_eq!(header.to_string(), "items=0-24");
+/// assert_eq!(
+///     header.resolve(10),
+///     Ok(vec![ResolvedRange { start: 0, end_inclusive: 9 }])
+/// );
+/// ```
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct ItemsRange(pub Vec<ByteRange>);
+
+impl ItemsRange {
+    pub fn parse(header: &str) -> Result<Self, RangeParseError> {
+        let range = RangeParser::new()
+            .register("items", IntRangeParser)
+            .parse(header)?;
+        match range.set {
+            RangeSet::Ranges(x) if range.unit.as_str().eq_ignore_ascii_case("items") => {
+                Ok(ItemsRange(x))
+            }
+            _ => Err(RangeParseError::UnsupportedUnit(range.unit.to_string())),
+        }
+    }
+
+    /// Resolves the requested ranges against a collection of `total` items, see
+    /// [`ByteRange::resolve_all`]
+    pub fn resolve(&self, total: u64) -> Result<Vec<ResolvedRange>, Unsatisfiable> {
+        ByteRange::resolve_all(&self.0, total)
+    }
+}
+
+impl fmt::Display for ItemsRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("items=")?;
+        fmt_int_ranges(&self.0, f)
+    }
+}
+
+/// `Content-Range` HTTP header with `items` unit
+///
+/// # Examples
+///
+/// ```rust
+/// use range_header::{ItemsContentRange, ResolvedRange, Unsatisfiable};
+/// let content_range = ItemsContentRange::Items {
+///     range: ResolvedRange { start: 0, end_inclusive: 24 },
+///     total: Some(66),
+/// };
+/// assert_eq!(content_range.to_string(), "items 0-24/66");
+///
+/// let unsatisfiable = Unsatisfiable { complete_length: 0 };
+/// assert_eq!(ItemsContentRange::from(unsatisfiable).to_string(), "items */0");
+/// ```
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum ItemsContentRange {
+    /// eg. `items 0-24/66`, or `items 0-24/*` when the total is unknown
+    Items {
+        range: ResolvedRange,
+        total: Option<u64>,
+    },
+    /// eg. `items */66`
+    Unsatisfied(u64),
+}
+
+impl From<Unsatisfiable> for ItemsContentRange {
+    fn from(x: Unsatisfiable) -> Self {
+        ItemsContentRange::Unsatisfied(x.complete_length)
+    }
+}
+
+impl fmt::Display for ItemsContentRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ItemsContentRange::Items {
+                range,
+                total: Some(total),
+            } => write!(f, "items {}-{}/{}", range.start, range.end_inclusive, total),
+            ItemsContentRange::Items { range, total: None } => {
+                write!(f, "items {}-{}/*", range.start, range.end_inclusive)
+            }
+            ItemsContentRange::Unsatisfied(total) => write!(f, "items */{}", total),
+        }
+    }
+}
+
+/// `OFFSET` and `LIMIT` of a database query selecting a resolved item range
+///
+/// # Examples
+///
+/// ```rust
+/// use range_header::{OffsetLimit, ResolvedRange};
+/// assert_eq!(
+///     OffsetLimit::from(ResolvedRange { start: 25, end_inclusive: 49 }),
+///     OffsetLimit { offset: 25, limit: 25 }
+/// );
+/// ```
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub struct OffsetLimit {
+    pub offset: u64,
+    pub limit: u64,
+}
+
+impl From<ResolvedRange> for OffsetLimit {
+    fn from(x: ResolvedRange) -> Self {
+        OffsetLimit {
+            offset: x.start,
+            limit: x.length(),
+        }
+    }
+}
@@ -1,3 +1,103 @@
+//! HTTP `Range` header parser.
+//!
+//! The crate is `no_std`. Parsing, resolution and `Content-Range` formatting work without any
+//! feature, `alloc` adds everything returning owned collections and `std` (the default) adds
+//! `If-Range`, `std::error::Error` implementations and [`RangeReader`], reading the ranges of
+//! any `Read + Seek` representation.
+//!
+//! The `http` feature converts the header types from and to `http::HeaderValue`, `headers`
+//! implements `headers::Header` for them, `axum`, `actix` and `rocket` add extractors and
+//! responses serving a [`Ranged`] body. `tower` adds a middleware serving ranges from the full
+//! responses of any service. `tokio` adds [`RangeReader`]'s async counterpart, a stream of the
+//! ranges of an `AsyncRead + AsyncSeek` source, and `hyper` a body streaming them.
+
+#![no_std]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "alloc")]
+pub use accept_ranges::{AcceptRanges, RangeUnit};
+#[cfg(feature = "axum")]
+pub use axum::RangeRejection;
 pub use byte_range::ByteRange;
+#[cfg(feature = "alloc")]
+pub use coalesce::CoalescedRange;
+pub use content_range::ContentRange;
+pub use error::RangeParseError;
+#[cfg(feature = "hyper")]
+pub use hyper::SeekableBody;
+#[cfg(feature = "std")]
+pub use if_range::{EntityTag, IfRange};
+#[cfg(feature = "alloc")]
+pub use items::{ItemsContentRange, ItemsRange, OffsetLimit};
+#[cfg(feature = "alloc")]
+pub use multipart::{MultipartByteranges, MultipartPart};
+#[cfg(feature = "alloc")]
+pub use multipart_parser::{MultipartError, MultipartEvent, MultipartParser};
+pub use parse_config::{ParseConfig, ParseMode};
+pub use parser::ByteRangeIter;
+#[cfg(feature = "alloc")]
+pub use policy::{PolicyAction, PolicyViolation, RangeDecision, RangePolicy};
+#[cfg(feature = "alloc")]
+pub use range::{IntRangeParser, Range, RangeParser, RangeSet, UnitParser};
+#[cfg(feature = "alloc")]
+pub use range_header::RangeHeader;
+#[cfg(feature = "std")]
+pub use reader::RangeReader;
+pub use resolve::{ResolvedRange, Unsatisfiable};
+#[cfg(feature = "std")]
+pub use serve::{RangeRequest, RangeResponse, Ranged, Representation, Seekable};
+#[cfg(feature = "tokio")]
+pub use tokio::RangeStream;
+#[cfg(feature = "tower")]
+pub use tower::{RangeBody, RangeBodyError, RangeLayer, RangeService, ResponseFuture};
 
+#[cfg(feature = "alloc")]
+mod accept_ranges;
+#[cfg(feature = "actix")]
+mod actix;
+#[cfg(feature = "tokio")]
+mod async_reader;
+#[cfg(feature = "axum")]
+mod axum;
 mod byte_range;
+#[cfg(feature = "alloc")]
+mod coalesce;
+mod content_range;
+mod error;
+#[cfg(feature = "http")]
+mod http_header;
+#[cfg(feature = "hyper")]
+mod hyper;
+#[cfg(feature = "std")]
+mod if_range;
+#[cfg(feature = "alloc")]
+mod items;
+#[cfg(feature = "alloc")]
+mod multipart;
+#[cfg(feature = "alloc")]
+mod multipart_parser;
+mod parse_config;
+mod parser;
+#[cfg(feature = "alloc")]
+mod policy;
+#[cfg(feature = "alloc")]
+mod range;
+#[cfg(feature = "alloc")]
+mod range_header;
+#[cfg(feature = "std")]
+mod reader;
+mod resolve;
+#[cfg(feature = "rocket")]
+mod rocket;
+#[cfg(feature = "std")]
+mod serve;
+#[cfg(feature = "tokio")]
+mod tokio;
+#[cfg(feature = "tower")]
+mod tower;
+#[cfg(feature = "headers")]
+mod typed_header;
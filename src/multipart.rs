@@ -0,0 +1,142 @@
+//! reference: <https://tools.ietf.org/html/rfc7233#appendix-A>
+
+use crate::{ContentRange, ResolvedRange};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// One part of a `multipart/byteranges` body, `header` is sent right before the bytes of `range`
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct MultipartPart {
+    /// Delimiter line and part headers, including the empty line ending them
+    pub header: String,
+    pub range: ResolvedRange,
+}
+
+/// Layout of a `multipart/byteranges` response body
+///
+/// The body is every part's `header` followed by the bytes of its `range`, then `closing`.
+///
+/// # Examples
+///
+/// ```rust
+/// use range_header::{MultipartByteranges, ResolvedRange};
+/// let multipart = MultipartByteranges::new(
+///     vec![
+///         ResolvedRange { start: 0, end_inclusive: 3 },
+///         ResolvedRange { start: 10, end_inclusive: 12 },
+///     ],
+///     "text/plain",
+///     20,
+///     "THIS_STRING_SEPARATES",
+/// );
+/// assert_eq!(
+///     multipart.content_type(),
+///     "multipart/byteranges; boundary=THIS_STRING_SEPARATES"
+/// );
+///
+/// let body = multipart.render(b"0123456789abcdefghij");
+/// assert_eq!(
+///     String::from_utf8(body.clone()).unwrap(),
+///     "--THIS_STRING_SEPARATES\r\n\
+///      Content-Type: text/plain\r\n\
+///      Content-Range: bytes 0-3/20\r\n\
+///      \r\n\
+///      0123\r\n\
+///      --THIS_STRING_SEPARATES\r\n\
+///      Content-Type: text/plain\r\n\
+///      Content-Range: bytes 10-12/20\r\n\
+///      \r\n\
+///      abc\r\n\
+///      --THIS_STRING_SEPARATES--\r\n"
+/// );
+/// assert_eq!(multipart.content_length(), body.len() as u64);
+/// ```
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct MultipartByteranges {
+    boundary: String,
+    parts: Vec<MultipartPart>,
+    closing: String,
+}
+
+impl MultipartByteranges {
+    /// `content_type` is the media type of the representation, `boundary` must not occur in any
+    /// of the selected bytes.
+    pub fn new(
+        ranges: Vec<ResolvedRange>,
+        content_type: &str,
+        complete_length: u64,
+        boundary: &str,
+    ) -> Self {
+        let parts = ranges
+            .into_iter()
+            .enumerate()
+            .map(|(index, range)| {
+                let content_range = ContentRange::Bytes {
+                    range,
+                    complete_length: Some(complete_length),
+                };
+                let header = format!(
+                    "{}--{}\r\nContent-Type: {}\r\nContent-Range: {}\r\n\r\n",
+                    if index == 0 { "" } else { "\r\n" },
+                    boundary,
+                    content_type,
+                    content_range
+                );
+                MultipartPart { header, range }
+            })
+            .collect();
+
+        MultipartByteranges {
+            boundary: boundary.to_string(),
+            parts,
+            closing: format!("\r\n--{}--\r\n", boundary),
+        }
+    }
+
+    /// Value of the response `Content-Type` header
+    pub fn content_type(&self) -> String {
+        format!("multipart/byteranges; boundary={}", self.boundary)
+    }
+
+    pub fn boundary(&self) -> &str {
+        &self.boundary
+    }
+
+    pub fn parts(&self) -> &[MultipartPart] {
+        &self.parts
+    }
+
+    /// Sent after the last part
+    pub fn closing(&self) -> &str {
+        &self.closing
+    }
+
+    /// Exact length of the whole body, value of the response `Content-Length` header
+    pub fn content_length(&self) -> u64 {
+        self.parts
+            .iter()
+            .map(|x| x.header.len() as u64 + x.range.length())
+            .sum::<u64>()
+            + self.closing.len() as u64
+    }
+
+    /// Builds the whole body from an in-memory representation
+    ///
+    /// # Panics
+    ///
+    /// Panics if any range is out of bounds of `representation`.
+    pub fn render(&self, representation: &[u8]) -> Vec<u8> {
+        let mut result = Vec::with_capacity(self.content_length() as usize);
+        for part in &self.parts {
+            result.extend_from_slice(part.header.as_bytes());
+            result.extend_from_slice(
+                &representation[part.range.start as usize..=part.range.end_inclusive as usize],
+            );
+        }
+        result.extend_from_slice(self.closing.as_bytes());
+        result
+    }
+}
@@ -0,0 +1,424 @@
+//! reference: <https://tools.ietf.org/html/rfc7233#appendix-A>, <https://tools.ietf.org/html/rfc2046#section-5.1.1>
+
+use crate::{ByteRange, ContentRange, RangeParseError, ResolvedRange};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+/// Something a [`MultipartParser`] found in the body
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub enum MultipartEvent {
+    /// Headers of a new part, `content_range` is always `ContentRange::Bytes`
+    PartStart {
+        content_range: ContentRange,
+        content_type: Option<String>,
+    },
+    /// Some bytes of the current part
+    Data(Vec<u8>),
+    /// The current part is complete
+    PartEnd,
+}
+
+/// Reason why a `multipart/byteranges` body was rejected
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub enum MultipartError {
+    /// `Content-Type` is not `multipart/byteranges` with a `boundary` parameter
+    InvalidContentType,
+    /// Delimiter or part header line is malformed
+    Malformed,
+    /// A part has no `Content-Range` header
+    MissingContentRange,
+    /// A part has an invalid or unsatisfied `Content-Range` header
+    InvalidContentRange(RangeParseError),
+    /// A part's `Content-Range` does not match its body length
+    LengthMismatch { expected: u64, actual: u64 },
+    /// Parts disagree on the complete length of the representation
+    InconsistentCompleteLength,
+    /// A part covers bytes that were not requested
+    UnrequestedRange(ResolvedRange),
+    /// The body ended before the closing delimiter
+    UnexpectedEof,
+}
+
+impl fmt::Display for MultipartError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MultipartError::InvalidContentType => {
+                f.write_str("content type is not multipart/byteranges with a boundary")
+            }
+            MultipartError::Malformed => f.write_str("malformed multipart body"),
+            MultipartError::MissingContentRange => f.write_str("part without Content-Range"),
+            MultipartError::InvalidContentRange(e) => write!(f, "invalid Content-Range: {}", e),
+            MultipartError::LengthMismatch { expected, actual } => write!(
+                f,
+                "part should have {} bytes, but has {} bytes",
+                expected, actual
+            ),
+            MultipartError::InconsistentCompleteLength => {
+                f.write_str("parts disagree on the complete length")
+            }
+            MultipartError::UnrequestedRange(range) => write!(
+                f,
+                "part {}-{} was not requested",
+                range.start, range.end_inclusive
+            ),
+            MultipartError::UnexpectedEof => f.write_str("multipart body ended unexpectedly"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MultipartError {}
+
+#[derive(Debug)]
+enum State {
+    Preamble,
+    Delimiter,
+    Headers {
+        content_range: Option<ContentRange>,
+        content_type: Option<String>,
+    },
+    Body {
+        expected: u64,
+        received: u64,
+    },
+    Epilogue,
+}
+
+/// Incremental parser of `multipart/byteranges` response bodies
+///
+/// Feed the body with [`MultipartParser::feed`] as it arrives, then drain events with
+/// [`MultipartParser::next_event`] until it returns `None`. Both CRLF and bare LF line
+/// endings are accepted, preamble and epilogue are skipped.
+///
+/// # Examples
+///
+/// ```rust
+/// use range_header::{ByteRange, ContentRange, MultipartEvent, MultipartParser, ResolvedRange};
+/// let mut parser =
+///     MultipartParser::from_content_type("multipart/byteranges; boundary=SEP").unwrap();
+/// parser.expect(ByteRange::parse("bytes=0-3"));
+///
+/// parser.feed(b"preamble\r\n--SEP\r\nContent-Range: bytes 0-3/20\r\n\r\n01");
+/// parser.feed(b"23\r\n--SEP--\r\n");
+///
+/// let mut events = Vec::new();
+/// while let Some(event) = parser.next_event().unwrap() {
+///     events.push(event);
+/// }
+/// parser.finish().unwrap();
+///
+/// assert_eq!(
+///     events,
+///     vec![
+///         MultipartEvent::PartStart {
+///             content_range: ContentRange::Bytes {
+///                 range: ResolvedRange { start: 0, end_inclusive: 3 },
+///                 complete_length: Some(20),
+///             },
+///             content_type: None,
+///         },
+///         MultipartEvent::Data(b"0123".to_vec()),
+///         MultipartEvent::PartEnd,
+///     ]
+/// );
+/// ```
+#[derive(Debug)]
+pub struct MultipartParser {
+    /// `\n--boundary`
+    delimiter: Vec<u8>,
+    buffer: Vec<u8>,
+    state: State,
+    requested: Option<Vec<ByteRange>>,
+    complete_length: Option<Option<u64>>,
+}
+
+impl MultipartParser {
+    pub fn new(boundary: &str) -> Self {
+        MultipartParser {
+            delimiter: format!("\n--{}", boundary).into_bytes(),
+            // the first delimiter may start the body without a line break before it
+            buffer: b"\n".to_vec(),
+            state: State::Preamble,
+            requested: None,
+            complete_length: None,
+        }
+    }
+
+    /// Takes the boundary from a response `Content-Type` header value
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use range_header::{MultipartError, MultipartParser};
+    /// assert!(MultipartParser::from_content_type("multipart/byteranges; boundary=\"a b\"").is_ok());
+    /// assert_eq!(
+    ///     MultipartParser::from_content_type("text/plain").unwrap_err(),
+    ///     MultipartError::InvalidContentType
+    /// );
+    /// ```
+    pub fn from_content_type(content_type: &str) -> Result<Self, MultipartError> {
+        let mut params = content_type.split(';');
+        let media_type = params.next().unwrap_or("").trim();
+        if !media_type.eq_ignore_ascii_case("multipart/byteranges") {
+            return Err(MultipartError::InvalidContentType);
+        }
+
+        for param in params {
+            let mut name_value = param.splitn(2, '=');
+            let name = name_value.next().unwrap_or("").trim();
+            let value = name_value.next().unwrap_or("").trim();
+            if !name.eq_ignore_ascii_case("boundary") {
+                continue;
+            }
+
+            let value = if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+                &value[1..value.len() - 1]
+            } else {
+                value
+            };
+            if value.is_empty() {
+                break;
+            }
+            return Ok(Self::new(value));
+        }
+        Err(MultipartError::InvalidContentType)
+    }
+
+    /// Rejects parts covering bytes outside of `requested`, servers may still coalesce
+    /// requested ranges into a single part.
+    pub fn expect(&mut self, requested: Vec<ByteRange>) {
+        self.requested = Some(requested);
+    }
+
+    /// Appends the next chunk of the body
+    pub fn feed(&mut self, chunk: &[u8]) {
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    /// Returns the next event, or `None` if more input is needed
+    pub fn next_event(&mut self) -> Result<Option<MultipartEvent>, MultipartError> {
+        loop {
+            match &mut self.state {
+                State::Preamble => match find(&self.buffer, &self.delimiter) {
+                    Some(index) => {
+                        self.buffer.drain(..index + self.delimiter.len());
+                        self.state = State::Delimiter;
+                    }
+                    None => {
+                        let keep = self.delimiter.len() - 1;
+                        let drop = self.buffer.len().saturating_sub(keep);
+                        self.buffer.drain(..drop);
+                        return Ok(None);
+                    }
+                },
+                State::Delimiter => {
+                    if self.buffer.starts_with(b"--") {
+                        self.state = State::Epilogue;
+                        continue;
+                    }
+                    let line = match self.take_line() {
+                        Some(x) => x,
+                        None if self.buffer == b"-" => return Ok(None),
+                        None if self
+                            .buffer
+                            .iter()
+                            .all(|x| is_transport_padding(*x) || *x == b'\r') =>
+                        {
+                            return Ok(None)
+                        }
+                        None => return Err(MultipartError::Malformed),
+                    };
+                    if !line.iter().all(|x| is_transport_padding(*x)) {
+                        return Err(MultipartError::Malformed);
+                    }
+                    self.state = State::Headers {
+                        content_range: None,
+                        content_type: None,
+                    };
+                }
+                State::Headers { .. } => {
+                    let line = match self.take_line() {
+                        Some(x) => x,
+                        None => return Ok(None),
+                    };
+                    if !line.is_empty() {
+                        self.parse_header_line(&line)?;
+                        continue;
+                    }
+
+                    let (content_range, content_type) = match &mut self.state {
+                        State::Headers {
+                            content_range,
+                            content_type,
+                        } => (content_range.take(), content_type.take()),
+                        _ => unreachable!(),
+                    };
+                    let content_range = content_range.ok_or(MultipartError::MissingContentRange)?;
+                    let expected = self.check_content_range(&content_range)?;
+                    self.state = State::Body {
+                        expected,
+                        received: 0,
+                    };
+                    return Ok(Some(MultipartEvent::PartStart {
+                        content_range,
+                        content_type,
+                    }));
+                }
+                State::Body { expected, received } => {
+                    if let Some(index) = find(&self.buffer, &self.delimiter) {
+                        if index > 0 {
+                            // the line break before a delimiter belongs to the delimiter
+                            let end = if self.buffer[index - 1] == b'\r' {
+                                index - 1
+                            } else {
+                                index
+                            };
+                            let data: Vec<u8> = self.buffer.drain(..index).take(end).collect();
+                            *received += data.len() as u64;
+                            if !data.is_empty() {
+                                return Ok(Some(MultipartEvent::Data(data)));
+                            }
+                            continue;
+                        }
+
+                        if *received != *expected {
+                            return Err(MultipartError::LengthMismatch {
+                                expected: *expected,
+                                actual: *received,
+                            });
+                        }
+                        self.buffer.drain(..self.delimiter.len());
+                        self.state = State::Delimiter;
+                        return Ok(Some(MultipartEvent::PartEnd));
+                    }
+
+                    // keep enough to recognize a `\r\n--boundary` split across chunks
+                    let keep = self.delimiter.len();
+                    if self.buffer.len() <= keep {
+                        return Ok(None);
+                    }
+                    let data: Vec<u8> = self.buffer.drain(..self.buffer.len() - keep).collect();
+                    *received += data.len() as u64;
+                    return Ok(Some(MultipartEvent::Data(data)));
+                }
+                State::Epilogue => {
+                    self.buffer.clear();
+                    return Ok(None);
+                }
+            }
+        }
+    }
+
+    /// Whether the closing delimiter was reached
+    pub fn is_finished(&self) -> bool {
+        matches!(self.state, State::Epilogue)
+    }
+
+    /// Checks that the whole body was consumed, call it once the body ended and
+    /// [`MultipartParser::next_event`] returned `None`
+    pub fn finish(&self) -> Result<(), MultipartError> {
+        if self.is_finished() {
+            Ok(())
+        } else {
+            Err(MultipartError::UnexpectedEof)
+        }
+    }
+
+    /// Removes a line from the buffer, without its line break
+    fn take_line(&mut self) -> Option<Vec<u8>> {
+        let index = self.buffer.iter().position(|x| *x == b'\n')?;
+        let mut line: Vec<u8> = self.buffer.drain(..=index).take(index).collect();
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        Some(line)
+    }
+
+    fn parse_header_line(&mut self, line: &[u8]) -> Result<(), MultipartError> {
+        let line = core::str::from_utf8(line).map_err(|_| MultipartError::Malformed)?;
+        let colon = line.find(':').ok_or(MultipartError::Malformed)?;
+        let name = line[..colon].trim();
+        let value = line[colon + 1..].trim();
+
+        let (content_range, content_type) = match &mut self.state {
+            State::Headers {
+                content_range,
+                content_type,
+            } => (content_range, content_type),
+            _ => unreachable!(),
+        };
+        if name.eq_ignore_ascii_case("Content-Range") {
+            let parsed = ContentRange::parse(value).map_err(MultipartError::InvalidContentRange)?;
+            *content_range = Some(parsed);
+        } else if name.eq_ignore_ascii_case("Content-Type") {
+            *content_type = Some(value.to_string());
+        }
+        Ok(())
+    }
+
+    /// Validates a part's `Content-Range`, returns the expected body length
+    fn check_content_range(&mut self, content_range: &ContentRange) -> Result<u64, MultipartError> {
+        let (range, complete_length) = match *content_range {
+            ContentRange::Bytes {
+                range,
+                complete_length,
+            } => (range, complete_length),
+            ContentRange::Unsatisfied(complete_length) => {
+                return Err(MultipartError::InvalidContentRange(
+                    RangeParseError::OutOfBounds {
+                        last: complete_length,
+                        complete_length,
+                    },
+                ))
+            }
+        };
+
+        match self.complete_length {
+            Some(x) if x != complete_length => {
+                return Err(MultipartError::InconsistentCompleteLength)
+            }
+            _ => self.complete_length = Some(complete_length),
+        }
+
+        if let Some(requested) = &self.requested {
+            if !is_requested(requested, range, complete_length) {
+                return Err(MultipartError::UnrequestedRange(range));
+            }
+        }
+        Ok(range.length())
+    }
+}
+
+fn is_requested(
+    requested: &[ByteRange],
+    range: ResolvedRange,
+    complete_length: Option<u64>,
+) -> bool {
+    let len = match complete_length {
+        Some(x) => x,
+        None if requested.iter().any(|x| matches!(x, ByteRange::Last(_))) => {
+            // a suffix can not be located without the complete length
+            return true;
+        }
+        None => u64::MAX,
+    };
+
+    match ByteRange::coalesce_all(requested, len, 0) {
+        Ok(x) => x
+            .iter()
+            .any(|x| x.range.start <= range.start && range.end_inclusive <= x.range.end_inclusive),
+        Err(_) => false,
+    }
+}
+
+fn is_transport_padding(x: u8) -> bool {
+    x == b' ' || x == b'\t'
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|x| x == needle)
+}
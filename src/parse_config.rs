@@ -0,0 +1,135 @@
+//! reference: <https://www.rfc-editor.org/rfc/rfc9110#section-14.1>, <https://www.rfc-editor.org/rfc/rfc9110#section-5.6.1>
+
+#[cfg(feature = "alloc")]
+use crate::byte_range::collect_vec;
+use crate::{byte_range::collect_buffer, ByteRange, ByteRangeIter, RangeParseError};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// How forgiving a [`ParseConfig`] is
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum ParseMode {
+    /// RFC 9110: the unit is case-insensitive, spaces, tabs and empty elements are only allowed
+    /// around commas, and an inverted spec rejects the whole header
+    Strict,
+    /// Like real-world clients: also accepts whitespace anywhere between tokens, `bytes 0-10`
+    /// without `=`, and drops inverted specs unless all of them are
+    Lenient,
+}
+
+/// Parser configuration for `bytes` Range headers
+///
+/// [`ByteRange::parse`] and friends accept the original grammar of this crate, which only
+/// allows spaces and a lowercase `bytes=`.
+///
+/// # Examples
+///
+/// ```rust
+/// use range_header::{ByteRange, ParseConfig, RangeParseError};
+/// let mut buffer = [ByteRange::FromTo(0); 4];
+///
+/// let strict = ParseConfig::strict();
+/// assert_eq!(
+///     strict.try_parse_into("Bytes=,\t0-99 ,, -50", &mut buffer),
+///     Ok(&[ByteRange::FromToAll(0, 99), ByteRange::Last(50)][..])
+/// );
+/// assert!(strict.try_parse_into("bytes=0 - 99", &mut buffer).is_err());
+/// assert_eq!(
+///     strict.try_parse_into("bytes=100-10", &mut buffer),
+///     Err(RangeParseError::InvertedRange { first: 100, last: 10 })
+/// );
+///
+/// let lenient = ParseConfig::lenient();
+/// assert_eq!(
+///     lenient.try_parse_into("bytes 0 - 99,100-10", &mut buffer),
+///     Ok(&[ByteRange::FromToAll(0, 99)][..])
+/// );
+/// assert_eq!(
+///     lenient.try_parse_into("bytes=100-10", &mut buffer),
+///     Err(RangeParseError::InvertedRange { first: 100, last: 10 })
+/// );
+/// ```
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub struct ParseConfig {
+    pub mode: ParseMode,
+}
+
+impl ParseConfig {
+    pub fn strict() -> Self {
+        ParseConfig {
+            mode: ParseMode::Strict,
+        }
+    }
+
+    pub fn lenient() -> Self {
+        ParseConfig {
+            mode: ParseMode::Lenient,
+        }
+    }
+
+    /// Parses lazily without allocating, see [`ByteRange::parse_iter`].
+    /// Inverted specs are yielded as errors in both modes.
+    pub fn parse_iter<'a>(&self, header: &'a str) -> ByteRangeIter<'a> {
+        self.parse_iter_bytes(header.as_bytes())
+    }
+
+    /// See [`ByteRange::parse_iter_bytes`]
+    pub fn parse_iter_bytes<'a>(&self, header: &'a [u8]) -> ByteRangeIter<'a> {
+        ByteRangeIter::new(header, Some(self.mode))
+    }
+
+    /// See [`ByteRange::try_parse`]
+    #[cfg(feature = "alloc")]
+    pub fn try_parse(&self, header: &str) -> Result<Vec<ByteRange>, RangeParseError> {
+        self.try_parse_bytes(header.as_bytes())
+    }
+
+    /// See [`ByteRange::try_parse_bytes`]
+    #[cfg(feature = "alloc")]
+    pub fn try_parse_bytes(&self, header: &[u8]) -> Result<Vec<ByteRange>, RangeParseError> {
+        let result = collect_vec(self.specs(header))?;
+        self.check_not_empty(header, result.is_empty())?;
+        Ok(result)
+    }
+
+    /// See [`ByteRange::try_parse_into`]
+    pub fn try_parse_into<'a>(
+        &self,
+        header: &str,
+        buffer: &'a mut [ByteRange],
+    ) -> Result<&'a [ByteRange], RangeParseError> {
+        let result = collect_buffer(self.specs(header.as_bytes()), buffer)?;
+        self.check_not_empty(header.as_bytes(), result.is_empty())?;
+        Ok(result)
+    }
+
+    /// Lenient mode drops inverted specs, if it dropped all of them the header is as invalid
+    /// as in strict mode
+    fn check_not_empty(&self, header: &[u8], is_empty: bool) -> Result<(), RangeParseError> {
+        if !is_empty {
+            return Ok(());
+        }
+        match self
+            .parse_iter_bytes(header)
+            .find(|x| matches!(x, Err(RangeParseError::InvertedRange { .. })))
+        {
+            Some(Err(e)) => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    fn specs<'a>(
+        &self,
+        header: &'a [u8],
+    ) -> impl Iterator<Item = Result<ByteRange, RangeParseError>> + 'a {
+        let lenient = self.mode == ParseMode::Lenient;
+        self.parse_iter_bytes(header)
+            .filter(move |x| !(lenient && matches!(x, Err(RangeParseError::InvertedRange { .. }))))
+    }
+}
+
+impl Default for ParseConfig {
+    fn default() -> Self {
+        Self::strict()
+    }
+}
@@ -0,0 +1,407 @@
+//! Hand-written, allocation-free building blocks of the header parsers.
+//!
+//! They accept exactly the language of the pest grammar in `tests/byte_range.pest`, which is
+//! kept as a differential testing oracle.
+
+use crate::{ByteRange, ParseMode, RangeParseError};
+use core::cmp::Ordering;
+
+#[derive(Debug, Clone)]
+pub(crate) struct Cursor<'a> {
+    input: &'a [u8],
+    pub(crate) pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub(crate) fn new(input: &'a [u8]) -> Self {
+        Cursor { input, pos: 0 }
+    }
+
+    pub(crate) fn peek(&self) -> Option<u8> {
+        self.input.get(self.pos).copied()
+    }
+
+    pub(crate) fn is_end(&self) -> bool {
+        self.pos == self.input.len()
+    }
+
+    pub(crate) fn peek_digit(&self) -> bool {
+        self.peek().is_some_and(|x| x.is_ascii_digit())
+    }
+
+    /// Skips the spaces allowed between tokens, `COMMENT` in the grammar
+    pub(crate) fn skip_spaces(&mut self) {
+        while self.peek() == Some(b' ') {
+            self.pos += 1;
+        }
+    }
+
+    /// Skips optional whitespace, spaces and tabs, returns whether there was any
+    pub(crate) fn skip_ows(&mut self) -> bool {
+        let start = self.pos;
+        while self.peek() == Some(b' ') || self.peek() == Some(b'\t') {
+            self.pos += 1;
+        }
+        self.pos != start
+    }
+
+    pub(crate) fn eat(&mut self, byte: u8) -> bool {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    pub(crate) fn eat_str(&mut self, s: &str) -> bool {
+        if self.input[self.pos..].starts_with(s.as_bytes()) {
+            self.pos += s.len();
+            return true;
+        }
+        false
+    }
+
+    pub(crate) fn eat_str_ignore_case(&mut self, s: &str) -> bool {
+        let end = self.pos + s.len();
+        if end <= self.input.len() && self.input[self.pos..end].eq_ignore_ascii_case(s.as_bytes()) {
+            self.pos = end;
+            return true;
+        }
+        false
+    }
+
+    pub(crate) fn expect(&mut self, byte: u8) -> Result<(), RangeParseError> {
+        if self.eat(byte) {
+            Ok(())
+        } else {
+            Err(self.syntax_error())
+        }
+    }
+
+    pub(crate) fn expect_end(&self) -> Result<(), RangeParseError> {
+        if self.is_end() {
+            Ok(())
+        } else {
+            Err(self.syntax_error())
+        }
+    }
+
+    /// Consumes a run of digits, the outer `Err` means there is no digit at all and the inner
+    /// one that the value overflows
+    pub(crate) fn digits(&mut self) -> Result<Result<u64, RangeParseError>, RangeParseError> {
+        let start = self.pos;
+        let mut value: Option<u64> = Some(0);
+        while let Some(x) = self.peek().filter(u8::is_ascii_digit) {
+            value = value
+                .and_then(|v| v.checked_mul(10))
+                .and_then(|v| v.checked_add(u64::from(x - b'0')));
+            self.pos += 1;
+        }
+
+        if self.pos == start {
+            return Err(self.syntax_error());
+        }
+        Ok(value.ok_or(RangeParseError::Overflow { offset: start }))
+    }
+
+    /// Consumes a run of digits of any length, saturating at `u64::MAX`
+    pub(crate) fn position(&mut self) -> Result<Position<'a>, RangeParseError> {
+        let start = self.pos;
+        let value = self.digits()?.unwrap_or(u64::MAX);
+        Ok(Position {
+            value,
+            digits: &self.input[start..self.pos],
+        })
+    }
+
+    /// Consumes a `token`, eg. a range unit
+    #[cfg(feature = "alloc")]
+    pub(crate) fn token(&mut self) -> Option<&'a str> {
+        let start = self.pos;
+        while self.peek().is_some_and(is_tchar) {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return None;
+        }
+        // tchar is ASCII only
+        Some(core::str::from_utf8(&self.input[start..self.pos]).unwrap())
+    }
+
+    pub(crate) fn syntax_error(&self) -> RangeParseError {
+        RangeParseError::Syntax { offset: self.pos }
+    }
+}
+
+/// A `first-pos`, `last-pos` or `suffix-length`, `value` saturates but `digits` keeps the
+/// exact number for comparisons
+pub(crate) struct Position<'a> {
+    value: u64,
+    digits: &'a [u8],
+}
+
+impl Position<'_> {
+    fn cmp_exact(&self, other: &Position) -> Ordering {
+        let trim = |x: &[u8]| {
+            let zeros = x.iter().take_while(|x| **x == b'0').count();
+            x.len() - zeros
+        };
+        let (a, b) = (trim(self.digits), trim(other.digits));
+        a.cmp(&b).then_with(|| {
+            self.digits[self.digits.len() - a..].cmp(&other.digits[other.digits.len() - b..])
+        })
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn is_tchar(x: u8) -> bool {
+    x.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&x)
+}
+
+/// Makes the offset of a syntax error found in a slice of a header relative to the header,
+/// `by` is where the slice starts
+#[cfg(feature = "alloc")]
+pub(crate) fn shift_offset(e: RangeParseError, by: usize) -> RangeParseError {
+    match e {
+        RangeParseError::Syntax { offset } => RangeParseError::Syntax {
+            offset: offset + by,
+        },
+        e => e,
+    }
+}
+
+/// Builds the error for a header that could not be parsed at `offset`, reporting the unit if
+/// the header starts with one other than `bytes` followed by `separator`
+#[cfg_attr(not(feature = "alloc"), allow(unused_variables))]
+pub(crate) fn unit_error(
+    header: &[u8],
+    separator: u8,
+    ignore_case: bool,
+    offset: usize,
+) -> RangeParseError {
+    #[cfg(feature = "alloc")]
+    {
+        let mut cursor = Cursor::new(header);
+        if let Some(unit) = cursor.token() {
+            let is_bytes = if ignore_case {
+                unit.eq_ignore_ascii_case("bytes")
+            } else {
+                unit == "bytes"
+            };
+            if cursor.eat(separator) && !is_bytes {
+                return RangeParseError::UnsupportedUnit(alloc::string::ToString::to_string(unit));
+            }
+        }
+    }
+    RangeParseError::Syntax { offset }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum IterState {
+    Unit,
+    First,
+    Next,
+    Done,
+}
+
+/// Iterator over the specs of a `Range` header, created by [`ByteRange::parse_iter`] or
+/// [`crate::ParseConfig::parse_iter`]
+///
+/// Yields an `Err` for every inverted spec, a syntax error ends the
+/// iteration.
+#[derive(Debug, Clone)]
+pub struct ByteRangeIter<'a> {
+    header: &'a [u8],
+    cursor: Cursor<'a>,
+    state: IterState,
+    /// `None` for the language of the pest grammar
+    mode: Option<ParseMode>,
+}
+
+impl<'a> ByteRangeIter<'a> {
+    /// Iterates a `bytes=` header
+    pub(crate) fn new(header: &'a [u8], mode: Option<ParseMode>) -> Self {
+        ByteRangeIter {
+            header,
+            cursor: Cursor::new(header),
+            state: IterState::Unit,
+            mode,
+        }
+    }
+
+    /// Iterates a bare range set, eg. `0-99,200-`
+    #[cfg(feature = "alloc")]
+    pub(crate) fn without_unit(set: &'a [u8]) -> Self {
+        ByteRangeIter {
+            header: set,
+            cursor: Cursor::new(set),
+            state: IterState::First,
+            mode: None,
+        }
+    }
+
+    /// Skips whitespace around commas
+    fn skip_list_whitespace(&mut self) {
+        match self.mode {
+            None => self.cursor.skip_spaces(),
+            Some(_) => {
+                self.cursor.skip_ows();
+            }
+        }
+    }
+
+    /// Skips whitespace between the tokens of a spec
+    fn skip_spec_whitespace(&mut self) {
+        match self.mode {
+            None => self.cursor.skip_spaces(),
+            Some(ParseMode::Strict) => {}
+            Some(ParseMode::Lenient) => {
+                self.cursor.skip_ows();
+            }
+        }
+    }
+
+    /// Consumes the range unit and the `=` after it
+    fn unit(&mut self) -> Result<(), RangeParseError> {
+        let mode = match self.mode {
+            None => {
+                self.cursor.skip_spaces();
+                if self.cursor.eat_str("bytes=") {
+                    return Ok(());
+                }
+                return Err(unit_error(self.header, b'=', false, self.cursor.pos));
+            }
+            Some(x) => x,
+        };
+
+        self.cursor.skip_ows();
+        if !self.cursor.eat_str_ignore_case("bytes") {
+            return Err(unit_error(self.header, b'=', true, self.cursor.pos));
+        }
+        match mode {
+            ParseMode::Strict => self.cursor.expect(b'='),
+            ParseMode::Lenient => {
+                // eg. 'bytes = 0-10' or 'bytes 0-10'
+                let whitespace = self.cursor.skip_ows();
+                if self.cursor.eat(b'=') || whitespace {
+                    Ok(())
+                } else {
+                    Err(self.cursor.syntax_error())
+                }
+            }
+        }
+    }
+
+    /// Positions beyond `u64::MAX` saturate, a representation can never be that long, so a
+    /// `first-pos` is unsatisfiable and a `last-pos` or `suffix-length` covers the rest of it
+    fn spec(&mut self) -> Result<Result<ByteRange, RangeParseError>, RangeParseError> {
+        if self.cursor.eat(b'-') {
+            // eg. '-200'
+            self.skip_spec_whitespace();
+            let suffix = self.cursor.position()?;
+            return Ok(Ok(ByteRange::Last(suffix.value)));
+        }
+
+        let first = self.cursor.position()?;
+        self.skip_spec_whitespace();
+        self.cursor.expect(b'-')?;
+        self.skip_spec_whitespace();
+        if !self.cursor.peek_digit() {
+            // eg. '200-'
+            return Ok(Ok(ByteRange::FromTo(first.value)));
+        }
+
+        // eg, '200-300'
+        let last = self.cursor.position()?;
+        if first.cmp_exact(&last) == Ordering::Greater {
+            return Ok(Err(RangeParseError::InvertedRange {
+                first: first.value,
+                last: last.value,
+            }));
+        }
+        Ok(Ok(ByteRange::FromToAll(first.value, last.value)))
+    }
+}
+
+impl Iterator for ByteRangeIter<'_> {
+    type Item = Result<ByteRange, RangeParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.state {
+                IterState::Unit => {
+                    if let Err(e) = self.unit() {
+                        self.state = IterState::Done;
+                        return Some(Err(e));
+                    }
+                    self.state = IterState::First;
+                }
+                IterState::First => {
+                    self.skip_list_whitespace();
+                    if self.mode.is_some() {
+                        // empty elements before the first one, eg. ', 0-10'
+                        while self.cursor.eat(b',') {
+                            self.cursor.skip_ows();
+                        }
+                    }
+                    break;
+                }
+                IterState::Next => {
+                    self.skip_list_whitespace();
+                    if self.cursor.is_end() {
+                        self.state = IterState::Done;
+                        return None;
+                    }
+                    if !self.cursor.eat(b',') {
+                        self.state = IterState::Done;
+                        return Some(Err(self.cursor.syntax_error()));
+                    }
+                    self.skip_list_whitespace();
+                    if self.cursor.peek_digit() || self.cursor.peek() == Some(b'-') {
+                        break;
+                    }
+                }
+                IterState::Done => return None,
+            }
+        }
+
+        match self.spec() {
+            Ok(x) => {
+                self.state = IterState::Next;
+                Some(x)
+            }
+            Err(e) => {
+                self.state = IterState::Done;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Feeds a whole range set to `push`, reporting syntax errors before invalid specs like pest
+/// did. Errors returned by `push` are reported like invalid specs.
+pub(crate) fn collect_ranges<I, F>(iter: I, mut push: F) -> Result<(), RangeParseError>
+where
+    I: Iterator<Item = Result<ByteRange, RangeParseError>>,
+    F: FnMut(ByteRange) -> Result<(), RangeParseError>,
+{
+    let mut spec_error = None;
+    for x in iter {
+        match x {
+            Ok(x) => {
+                if let Err(e) = push(x) {
+                    spec_error.get_or_insert(e);
+                }
+            }
+            Err(e @ RangeParseError::InvertedRange { .. }) => {
+                spec_error.get_or_insert(e);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    match spec_error {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,293 @@
+//! Protection against abusive multi-range requests, eg. CVE-2011-3192
+//!
+//! reference: <https://tools.ietf.org/html/rfc7233#section-6.1>
+
+use crate::{ByteRange, ResolvedRange, Unsatisfiable};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// What to do with a request that violates a [`RangePolicy`]
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum PolicyAction {
+    /// Respond `416 Range Not Satisfiable`
+    Reject,
+    /// Ignore the `Range` header and respond `200 OK` with the full representation
+    Fallback,
+    /// Coalesce the ranges, falling back to the full representation if still too many
+    Coalesce,
+}
+
+/// The limit of a [`RangePolicy`] that a request exceeded
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum PolicyViolation {
+    /// More range specs than `max_ranges`
+    TooManyRanges { count: usize, max: usize },
+    /// More overlapping ranges than `max_overlaps`
+    TooManyOverlaps { count: usize, max: usize },
+    /// More small out-of-order ranges than `max_reversals`
+    TooManyReversals { count: usize, max: usize },
+    /// More bytes requested in total than `max_total_ratio` times the representation length
+    TooManyBytes { requested: u64, max: u64 },
+}
+
+impl fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PolicyViolation::TooManyRanges { count, max } => {
+                write!(f, "{} ranges requested, at most {} allowed", count, max)
+            }
+            PolicyViolation::TooManyOverlaps { count, max } => {
+                write!(f, "{} overlapping ranges, at most {} allowed", count, max)
+            }
+            PolicyViolation::TooManyReversals { count, max } => {
+                write!(f, "{} out-of-order ranges, at most {} allowed", count, max)
+            }
+            PolicyViolation::TooManyBytes { requested, max } => {
+                write!(f, "{} bytes requested, at most {} allowed", requested, max)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PolicyViolation {}
+
+/// Outcome of [`RangePolicy::apply`]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub enum RangeDecision {
+    /// Respond `206 Partial Content` with these ranges
+    Partial(Vec<ResolvedRange>),
+    /// Respond `200 OK` with the full representation
+    Full(PolicyViolation),
+    /// Respond `416 Range Not Satisfiable`, `violation` is `None` if no range is satisfiable
+    NotSatisfiable {
+        unsatisfiable: Unsatisfiable,
+        violation: Option<PolicyViolation>,
+    },
+}
+
+/// Limits applied to a range set before serving it, `None` disables a limit
+///
+/// Defaults follow Apache httpd: at most 200 ranges, 20 overlaps and 20 reversals, falling back
+/// to the full representation otherwise.
+///
+/// # Examples
+///
+/// ```rust
+/// use range_header::{ByteRange, PolicyViolation, RangeDecision, RangePolicy, ResolvedRange};
+/// let policy = RangePolicy::default();
+///
+/// let ranges = ByteRange::parse("bytes=0-99,200-299");
+/// assert_eq!(
+///     policy.apply(&ranges, 1000),
+///     RangeDecision::Partial(vec![
+///         ResolvedRange { start: 0, end_inclusive: 99 },
+///         ResolvedRange { start: 200, end_inclusive: 299 },
+///     ])
+/// );
+///
+/// let attack = format!("bytes={}", vec!["0-"; 300].join(","));
+/// assert_eq!(
+///     policy.apply(&ByteRange::parse(&attack), 1000),
+///     RangeDecision::Full(PolicyViolation::TooManyRanges { count: 300, max: 200 })
+/// );
+/// ```
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct RangePolicy {
+    /// Maximum number of range specs, counting unsatisfiable ones
+    pub max_ranges: Option<usize>,
+    /// Maximum number of ranges overlapping an earlier one, `Some(0)` forbids any overlap
+    pub max_overlaps: Option<usize>,
+    /// Maximum number of ranges starting before the range requested just before them
+    pub max_reversals: Option<usize>,
+    /// Only ranges shorter than this count as reversals
+    pub reversal_size: u64,
+    /// Maximum total bytes requested, as a multiple of the representation length
+    pub max_total_ratio: Option<u64>,
+    /// What to do with a request exceeding any limit
+    pub action: PolicyAction,
+    /// Ranges separated by at most this many bytes are merged by [`PolicyAction::Coalesce`]
+    pub coalesce_gap: u64,
+}
+
+impl Default for RangePolicy {
+    fn default() -> Self {
+        RangePolicy {
+            max_ranges: Some(200),
+            max_overlaps: Some(20),
+            max_reversals: Some(20),
+            reversal_size: u64::MAX,
+            max_total_ratio: None,
+            action: PolicyAction::Fallback,
+            coalesce_gap: 0,
+        }
+    }
+}
+
+impl RangePolicy {
+    /// A policy without any limit
+    pub fn unlimited() -> Self {
+        RangePolicy {
+            max_ranges: None,
+            max_overlaps: None,
+            max_reversals: None,
+            reversal_size: u64::MAX,
+            max_total_ratio: None,
+            action: PolicyAction::Fallback,
+            coalesce_gap: 0,
+        }
+    }
+
+    /// Decides how to respond to `ranges` for a representation of `len` bytes
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use range_header::{
+    ///     ByteRange, PolicyAction, PolicyViolation, RangeDecision, RangePolicy, ResolvedRange,
+    ///     Unsatisfiable,
+    /// };
+    /// let ranges = ByteRange::parse("bytes=0-99,50-149,-100");
+    ///
+    /// let policy = RangePolicy {
+    ///     max_overlaps: Some(0),
+    ///     action: PolicyAction::Coalesce,
+    ///     ..RangePolicy::default()
+    /// };
+    /// assert_eq!(
+    ///     policy.apply(&ranges, 1000),
+    ///     RangeDecision::Partial(vec![
+    ///         ResolvedRange { start: 0, end_inclusive: 149 },
+    ///         ResolvedRange { start: 900, end_inclusive: 999 },
+    ///     ])
+    /// );
+    ///
+    /// let policy = RangePolicy {
+    ///     max_overlaps: Some(0),
+    ///     action: PolicyAction::Reject,
+    ///     ..RangePolicy::default()
+    /// };
+    /// assert_eq!(
+    ///     policy.apply(&ranges, 1000),
+    ///     RangeDecision::NotSatisfiable {
+    ///         unsatisfiable: Unsatisfiable { complete_length: 1000 },
+    ///         violation: Some(PolicyViolation::TooManyOverlaps { count: 1, max: 0 }),
+    ///     }
+    /// );
+    ///
+    /// assert_eq!(
+    ///     policy.apply(&ByteRange::parse("bytes=1000-"), 1000),
+    ///     RangeDecision::NotSatisfiable {
+    ///         unsatisfiable: Unsatisfiable { complete_length: 1000 },
+    ///         violation: None,
+    ///     }
+    /// );
+    /// ```
+    pub fn apply(&self, ranges: &[ByteRange], len: u64) -> RangeDecision {
+        if let Some(max) = self.max_ranges {
+            if ranges.len() > max && self.action != PolicyAction::Coalesce {
+                return self.violated(
+                    PolicyViolation::TooManyRanges {
+                        count: ranges.len(),
+                        max,
+                    },
+                    len,
+                );
+            }
+        }
+
+        let resolved = match ByteRange::resolve_all(ranges, len) {
+            Ok(x) => x,
+            Err(unsatisfiable) => {
+                return RangeDecision::NotSatisfiable {
+                    unsatisfiable,
+                    violation: None,
+                }
+            }
+        };
+
+        let violation = match self.check(ranges.len(), &resolved, len) {
+            None => return RangeDecision::Partial(resolved),
+            Some(x) => x,
+        };
+        if self.action != PolicyAction::Coalesce {
+            return self.violated(violation, len);
+        }
+
+        let coalesced: Vec<ResolvedRange> = ResolvedRange::coalesce(&resolved, self.coalesce_gap)
+            .into_iter()
+            .map(|x| x.range)
+            .collect();
+        match self.check(coalesced.len(), &coalesced, len) {
+            None => RangeDecision::Partial(coalesced),
+            Some(x) => RangeDecision::Full(x),
+        }
+    }
+
+    fn violated(&self, violation: PolicyViolation, len: u64) -> RangeDecision {
+        match self.action {
+            PolicyAction::Reject => RangeDecision::NotSatisfiable {
+                unsatisfiable: Unsatisfiable {
+                    complete_length: len,
+                },
+                violation: Some(violation),
+            },
+            PolicyAction::Fallback | PolicyAction::Coalesce => RangeDecision::Full(violation),
+        }
+    }
+
+    fn check(&self, count: usize, resolved: &[ResolvedRange], len: u64) -> Option<PolicyViolation> {
+        if let Some(max) = self.max_ranges {
+            if count > max {
+                return Some(PolicyViolation::TooManyRanges { count, max });
+            }
+        }
+
+        if let Some(max) = self.max_overlaps {
+            let count = count_overlaps(resolved);
+            if count > max {
+                return Some(PolicyViolation::TooManyOverlaps { count, max });
+            }
+        }
+
+        if let Some(max) = self.max_reversals {
+            let count = resolved
+                .windows(2)
+                .filter(|x| x[1].start < x[0].start && x[1].length() < self.reversal_size)
+                .count();
+            if count > max {
+                return Some(PolicyViolation::TooManyReversals { count, max });
+            }
+        }
+
+        if let Some(ratio) = self.max_total_ratio {
+            let requested = resolved
+                .iter()
+                .fold(0u64, |acc, x| acc.saturating_add(x.length()));
+            let max = len.saturating_mul(ratio);
+            if requested > max {
+                return Some(PolicyViolation::TooManyBytes { requested, max });
+            }
+        }
+
+        None
+    }
+}
+
+fn count_overlaps(resolved: &[ResolvedRange]) -> usize {
+    let mut sorted = resolved.to_vec();
+    sorted.sort_by_key(|x| x.start);
+
+    let mut count = 0;
+    let mut covered_until: Option<u64> = None;
+    for range in sorted {
+        match covered_until {
+            Some(end) if range.start <= end => {
+                count += 1;
+                covered_until = Some(end.max(range.end_inclusive));
+            }
+            _ => covered_until = Some(range.end_inclusive),
+        }
+    }
+    count
+}
@@ -0,0 +1,197 @@
+//! reference: <https://tools.ietf.org/html/rfc7233#section-3.1>
+
+use crate::{
+    byte_range::{fmt_int_ranges, parse_int_ranges},
+    parser::{shift_offset, Cursor},
+    ByteRange, RangeParseError, RangeUnit,
+};
+use alloc::{
+    boxed::Box,
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+/// Ranges requested in a `Range` header of any unit
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub enum RangeSet {
+    /// `int-range`s and `suffix-range`s, eg. `0-99,200-,-50`
+    Ranges(Vec<ByteRange>),
+    /// `other-range-set` of a unit without a registered parser, kept verbatim
+    Other(String),
+}
+
+/// `Range` HTTP header of any unit, eg. `bytes=0-99` or `items=0-24`
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct Range {
+    pub unit: RangeUnit,
+    pub set: RangeSet,
+}
+
+impl Range {
+    /// Parses Range HTTP header string with the default [`RangeParser`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use range_header::{ByteRange, Range, RangeSet, RangeUnit};
+    /// assert_eq!(
+    ///     Range::parse("bytes=0-99"),
+    ///     Ok(Range {
+    ///         unit: RangeUnit::Bytes,
+    ///         set: RangeSet::Ranges(vec![ByteRange::FromToAll(0, 99)]),
+    ///     })
+    /// );
+    ///
+    /// assert_eq!(
+    ///     Range::parse("items=0-24"),
+    ///     Ok(Range {
+    ///         unit: RangeUnit::from("items"),
+    ///         set: RangeSet::Other("0-24".to_string()),
+    ///     })
+    /// );
+    /// ```
+    pub fn parse(header: &str) -> Result<Self, RangeParseError> {
+        RangeParser::new().parse(header)
+    }
+
+    /// Like [`Range::parse`], for header values that may not be UTF-8
+    pub fn parse_bytes(header: &[u8]) -> Result<Self, RangeParseError> {
+        RangeParser::new().parse_bytes(header)
+    }
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}=", self.unit)?;
+        match &self.set {
+            RangeSet::Ranges(x) => fmt_int_ranges(x, f),
+            RangeSet::Other(x) => f.write_str(x),
+        }
+    }
+}
+
+/// Parses the range set of one unit, offsets in errors are relative to `set`
+pub trait UnitParser: Send + Sync {
+    fn parse(&self, set: &str) -> Result<RangeSet, RangeParseError>;
+}
+
+impl<F> UnitParser for F
+where
+    F: Fn(&str) -> Result<RangeSet, RangeParseError> + Send + Sync,
+{
+    fn parse(&self, set: &str) -> Result<RangeSet, RangeParseError> {
+        self(set)
+    }
+}
+
+/// Parses range sets with the same syntax as `bytes`, into [`RangeSet::Ranges`]
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash, Default)]
+pub struct IntRangeParser;
+
+impl UnitParser for IntRangeParser {
+    fn parse(&self, set: &str) -> Result<RangeSet, RangeParseError> {
+        parse_int_ranges(set).map(RangeSet::Ranges)
+    }
+}
+
+/// `Range` header parser with a parser registered per unit
+///
+/// Only `bytes` is registered by default, sets of other units are kept verbatim.
+///
+/// # Examples
+///
+/// ```rust
+/// use range_header::{ByteRange, IntRangeParser, RangeParser, RangeSet};
+/// let mut parser = RangeParser::new();
+/// parser.register("items", IntRangeParser);
+/// parser.register("pages", |set: &str| Ok(RangeSet::Other(set.to_uppercase())));
+///
+/// assert_eq!(
+///     parser.parse("items=0-24").unwrap().set,
+///     RangeSet::Ranges(vec![ByteRange::FromToAll(0, 24)])
+/// );
+/// assert_eq!(
+///     parser.parse("pages=first").unwrap().set,
+///     RangeSet::Other("FIRST".to_string())
+/// );
+/// ```
+pub struct RangeParser {
+    units: BTreeMap<String, Box<dyn UnitParser>>,
+}
+
+impl RangeParser {
+    pub fn new() -> Self {
+        let mut result = RangeParser {
+            units: BTreeMap::new(),
+        };
+        result.register("bytes", IntRangeParser);
+        result
+    }
+
+    /// Registers `parser` for `unit`, replacing any previous one. Units are case-insensitive.
+    pub fn register<P: UnitParser + 'static>(&mut self, unit: &str, parser: P) -> &mut Self {
+        self.units
+            .insert(unit.to_ascii_lowercase(), Box::new(parser));
+        self
+    }
+
+    /// Parses Range HTTP header string, offsets in errors are relative to `header`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use range_header::{RangeParseError, RangeParser};
+    /// assert_eq!(
+    ///     RangeParser::new().parse("bytes=0-x").unwrap_err(),
+    ///     RangeParseError::Syntax { offset: 8 }
+    /// );
+    /// ```
+    pub fn parse(&self, header: &str) -> Result<Range, RangeParseError> {
+        self.parse_bytes(header.as_bytes())
+    }
+
+    /// Like [`RangeParser::parse`], for header values that may not be UTF-8
+    pub fn parse_bytes(&self, header: &[u8]) -> Result<Range, RangeParseError> {
+        let mut cursor = Cursor::new(header);
+        let unit = cursor.token().ok_or_else(|| cursor.syntax_error())?;
+        cursor.expect(b'=')?;
+        let set_start = cursor.pos;
+        while cursor
+            .peek()
+            .is_some_and(|x| (b'!'..=b'~').contains(&x) || x == b' ' || x == b'\t')
+        {
+            cursor.pos += 1;
+        }
+        if cursor.pos == set_start {
+            return Err(cursor.syntax_error());
+        }
+        cursor.expect_end()?;
+        // only printable ASCII so far
+        let set = core::str::from_utf8(&header[set_start..]).unwrap();
+
+        let parsed = match self.units.get(&unit.to_ascii_lowercase()) {
+            Some(parser) => parser.parse(set).map_err(|e| shift_offset(e, set_start))?,
+            None => RangeSet::Other(set.to_string()),
+        };
+        Ok(Range {
+            unit: RangeUnit::from(unit),
+            set: parsed,
+        })
+    }
+}
+
+impl Default for RangeParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for RangeParser {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RangeParser")
+            .field("units", &self.units.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
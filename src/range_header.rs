@@ -0,0 +1,66 @@
+#[cfg(feature = "std")]
+use crate::ParseConfig;
+use crate::{byte_range::fmt_int_ranges, ByteRange, RangeParseError};
+use alloc::vec::Vec;
+use core::{fmt, str::FromStr};
+
+/// A whole `Range` HTTP header, formats back to `bytes=0-99,200-,-50`.
+///
+/// For any set without inverted ranges, parsing the formatted string gives back the same set.
+/// A `Range` header has at least one spec, so there is no empty default.
+///
+/// # Examples
+///
+/// ```rust
+/// use range_header::{ByteRange, RangeHeader};
+/// let header = RangeHeader(vec![
+///     ByteRange::FromToAll(0, 99),
+///     ByteRange::FromTo(200),
+///     ByteRange::Last(50),
+/// ]);
+/// assert_eq!(header.to_string(), "bytes=0-99,200-,-50");
+/// assert_eq!(header.to_string().parse(), Ok(header));
+/// ```
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct RangeHeader(pub Vec<ByteRange>);
+
+impl From<Vec<ByteRange>> for RangeHeader {
+    fn from(x: Vec<ByteRange>) -> Self {
+        RangeHeader(x)
+    }
+}
+
+#[cfg(feature = "std")]
+impl RangeHeader {
+    /// Parses the values of several `Range` fields into one set, in order, `None` if there is
+    /// none
+    pub(crate) fn parse_fields<'a, I>(
+        fields: I,
+        config: ParseConfig,
+    ) -> Result<Option<Self>, RangeParseError>
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        let mut result = None;
+        for field in fields {
+            let ranges = config.try_parse_bytes(field)?;
+            result.get_or_insert_with(Vec::new).extend(ranges);
+        }
+        Ok(result.map(RangeHeader))
+    }
+}
+
+impl FromStr for RangeHeader {
+    type Err = RangeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ByteRange::try_parse(s).map(RangeHeader)
+    }
+}
+
+impl fmt::Display for RangeHeader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("bytes=")?;
+        fmt_int_ranges(&self.0, f)
+    }
+}
@@ -0,0 +1,173 @@
+//! Reading the body of a ranged response from a seekable representation
+
+use crate::{serve::Segment, MultipartByteranges, RangeResponse, ResolvedRange};
+use alloc::{collections::VecDeque, vec::Vec};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+/// Default largest chunk read from a source at once by the streaming bodies
+#[cfg(any(feature = "actix", feature = "tokio"))]
+pub(crate) const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Reads exactly the requested bytes of a seekable representation, such as a [`std::fs::File`],
+/// seeking to the start of every range
+///
+/// It is a [`Read`], so [`io::copy`] writes the whole body to any [`Write`]. Reading fails with
+/// [`io::ErrorKind::UnexpectedEof`] if the representation ends within a range.
+///
+/// # Examples
+///
+/// ```rust
+/// use range_header::{RangeReader, ResolvedRange};
+/// use std::io::{Cursor, Read};
+/// let ranges = [
+///     ResolvedRange { start: 6, end_inclusive: 10 },
+///     ResolvedRange { start: 0, end_inclusive: 4 },
+/// ];
+/// let mut reader = RangeReader::new(Cursor::new("hello world"), ranges);
+/// assert_eq!(reader.remaining(), 10);
+///
+/// let mut body = String::new();
+/// reader.read_to_string(&mut body).unwrap();
+/// assert_eq!(body, "worldhello");
+/// ```
+#[derive(Debug)]
+pub struct RangeReader<R> {
+    inner: R,
+    segments: VecDeque<Segment>,
+    /// Bytes of the front segment already read
+    offset: u64,
+    remaining: u64,
+}
+
+impl<R> RangeReader<R> {
+    /// Reads `ranges` one after another, without any framing
+    pub fn new<I: IntoIterator<Item = ResolvedRange>>(inner: R, ranges: I) -> Self {
+        Self::from_segments(inner, ranges.into_iter().map(Segment::Range).collect())
+    }
+
+    /// Reads a `multipart/byteranges` body, the parts interleaved with their boundary lines and
+    /// headers
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use range_header::{MultipartByteranges, RangeReader, ResolvedRange};
+    /// use std::io::{Cursor, Read};
+    /// let multipart = MultipartByteranges::new(
+    ///     vec![
+    ///         ResolvedRange { start: 0, end_inclusive: 4 },
+    ///         ResolvedRange { start: 6, end_inclusive: 10 },
+    ///     ],
+    ///     "text/plain",
+    ///     11,
+    ///     "THIS_STRING_SEPARATES",
+    /// );
+    /// let mut body = Vec::new();
+    /// RangeReader::multipart(Cursor::new("hello world"), &multipart)
+    ///     .read_to_end(&mut body)
+    ///     .unwrap();
+    /// assert_eq!(body, multipart.render(b"hello world"));
+    /// ```
+    pub fn multipart(inner: R, multipart: &MultipartByteranges) -> Self {
+        Self::from_segments(inner, Segment::multipart(multipart))
+    }
+
+    /// Reads the body of `response`, the whole representation for `200 OK` and nothing for
+    /// `416 Range Not Satisfiable`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use range_header::{RangePolicy, RangeReader, RangeRequest, RangeResponse, Representation};
+    /// use std::io::{self, Cursor};
+    /// let request = RangeRequest::parse_bytes(Some(b"bytes=-5"), None);
+    /// let response = RangeResponse::new(&request, &Representation::new(11), &RangePolicy::default());
+    ///
+    /// let mut reader = RangeReader::from_response(Cursor::new("hello world"), &response);
+    /// let mut body = Vec::new();
+    /// assert_eq!(io::copy(&mut reader, &mut body).unwrap(), response.content_length());
+    /// assert_eq!(body, b"world");
+    /// ```
+    pub fn from_response(inner: R, response: &RangeResponse) -> Self {
+        Self::from_segments(inner, response.segments())
+    }
+
+    fn from_segments(inner: R, segments: Vec<Segment>) -> Self {
+        RangeReader {
+            inner,
+            remaining: segments.iter().map(Segment::length).sum(),
+            segments: segments.into(),
+            offset: 0,
+        }
+    }
+
+    /// Bytes left to read
+    pub fn remaining(&self) -> u64 {
+        self.remaining
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read + Seek> RangeReader<R> {
+    /// Writes the rest of the body to `writer`, returns the number of bytes written
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use range_header::{RangeReader, ResolvedRange};
+    /// use std::io::Cursor;
+    /// let ranges = [ResolvedRange { start: 0, end_inclusive: 4 }];
+    /// let mut body = Vec::new();
+    /// let written = RangeReader::new(Cursor::new("hello world"), ranges)
+    ///     .write_to(&mut body)
+    ///     .unwrap();
+    /// assert_eq!(written, 5);
+    /// assert_eq!(body, b"hello");
+    /// ```
+    pub fn write_to<W: Write + ?Sized>(&mut self, writer: &mut W) -> io::Result<u64> {
+        io::copy(self, writer)
+    }
+}
+
+impl<R: Read + Seek> Read for RangeReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            let read = match self.segments.front() {
+                None => return Ok(0),
+                Some(Segment::Static(x)) => {
+                    let rest = &x.as_bytes()[self.offset as usize..];
+                    let len = rest.len().min(buf.len());
+                    buf[..len].copy_from_slice(&rest[..len]);
+                    len
+                }
+                Some(Segment::Range(range)) => {
+                    if self.offset == 0 && range.length() > 0 {
+                        self.inner.seek(SeekFrom::Start(range.start))?;
+                    }
+                    let len = (range.length() - self.offset).min(buf.len() as u64) as usize;
+                    let read = self.inner.read(&mut buf[..len])?;
+                    if read == 0 && len > 0 {
+                        return Err(io::ErrorKind::UnexpectedEof.into());
+                    }
+                    read
+                }
+            };
+
+            self.offset += read as u64;
+            self.remaining -= read as u64;
+            if self.segments.front().map(Segment::length) == Some(self.offset) {
+                self.segments.pop_front();
+                self.offset = 0;
+            }
+            if read > 0 {
+                return Ok(read);
+            }
+        }
+    }
+}
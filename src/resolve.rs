@@ -0,0 +1,124 @@
+//! reference: <https://tools.ietf.org/html/rfc7233#section-2.1>
+
+use crate::ByteRange;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+
+/// A satisfiable byte range with concrete, inclusive offsets into the representation
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub struct ResolvedRange {
+    pub start: u64,
+    pub end_inclusive: u64,
+}
+
+impl ResolvedRange {
+    /// Number of bytes covered by this range, never zero
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use range_header::ResolvedRange;
+    /// let range = ResolvedRange { start: 0, end_inclusive: 499 };
+    /// assert_eq!(range.length(), 500);
+    /// ```
+    pub fn length(&self) -> u64 {
+        self.end_inclusive - self.start + 1
+    }
+}
+
+/// None of the requested ranges overlap the representation, the server should respond with
+/// `416 Range Not Satisfiable` and `Content-Range: bytes */{complete_length}`
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub struct Unsatisfiable {
+    pub complete_length: u64,
+}
+
+impl fmt::Display for Unsatisfiable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "no range is satisfiable for a representation of {} bytes",
+            self.complete_length
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Unsatisfiable {}
+
+impl ByteRange {
+    /// Resolves this range against a representation of `len` bytes as per RFC 7233 section 2.1.
+    /// Returns `None` if the range is not satisfiable.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use range_header::{ByteRange, ResolvedRange};
+    /// assert_eq!(
+    ///     ByteRange::FromToAll(500, 2000).resolve(1000),
+    ///     Some(ResolvedRange { start: 500, end_inclusive: 999 })
+    /// );
+    ///
+    /// assert_eq!(
+    ///     ByteRange::Last(2000).resolve(1000),
+    ///     Some(ResolvedRange { start: 0, end_inclusive: 999 })
+    /// );
+    ///
+    /// assert_eq!(ByteRange::FromTo(1000).resolve(1000), None);
+    /// assert_eq!(ByteRange::Last(0).resolve(1000), None);
+    /// assert_eq!(ByteRange::FromTo(0).resolve(0), None);
+    /// ```
+    pub fn resolve(&self, len: u64) -> Option<ResolvedRange> {
+        if len == 0 {
+            return None;
+        }
+
+        let (start, end_inclusive) = match *self {
+            ByteRange::FromTo(first) => (first, len - 1),
+            ByteRange::FromToAll(first, last) => (first, last.min(len - 1)),
+            ByteRange::Last(0) => return None,
+            ByteRange::Last(suffix) => (len - suffix.min(len), len - 1),
+        };
+
+        if start > end_inclusive {
+            return None;
+        }
+        Some(ResolvedRange {
+            start,
+            end_inclusive,
+        })
+    }
+
+    /// Resolves a whole range set against a representation of `len` bytes, dropping
+    /// unsatisfiable ranges and keeping the request order.
+    /// Returns `Err` if no range is satisfiable.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use range_header::{ByteRange, ResolvedRange, Unsatisfiable};
+    /// assert_eq!(
+    ///     ByteRange::resolve_all(&[ByteRange::FromTo(2000), ByteRange::Last(100)], 1000),
+    ///     Ok(vec![ResolvedRange { start: 900, end_inclusive: 999 }])
+    /// );
+    ///
+    /// assert_eq!(
+    ///     ByteRange::resolve_all(&[ByteRange::FromTo(2000)], 1000),
+    ///     Err(Unsatisfiable { complete_length: 1000 })
+    /// );
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn resolve_all(
+        ranges: &[ByteRange],
+        len: u64,
+    ) -> Result<Vec<ResolvedRange>, Unsatisfiable> {
+        let result: Vec<ResolvedRange> = ranges.iter().filter_map(|x| x.resolve(len)).collect();
+        if result.is_empty() {
+            return Err(Unsatisfiable {
+                complete_length: len,
+            });
+        }
+        Ok(result)
+    }
+}
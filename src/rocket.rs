@@ -0,0 +1,123 @@
+//! Rocket request guard and in-memory `200`/`206`/`416` responses
+
+use crate::{ParseConfig, RangeRequest, RangeResponse, Ranged};
+use alloc::{boxed::Box, vec::Vec};
+use core::convert::Infallible;
+use rocket::{
+    http::{Method, Status},
+    request::{FromRequest, Outcome, Request},
+    response::{self, Responder, Response},
+};
+use std::io::Cursor;
+
+/// Reads the `Range` and `If-Range` headers of `GET` requests, see
+/// [`RangeRequest::parse_bytes`]. Specs of several `Range` fields are joined into one set,
+/// other methods always get the full representation and the guard never fails.
+///
+/// # Examples
+///
+/// ```rust
+/// use range_header::RangeRequest;
+/// use rocket::{http::Header, local::blocking::Client};
+///
+/// fn describe(request: RangeRequest) -> String {
+///     match request.ranges {
+///         Some(ranges) => format!("{} ranges requested", ranges.len()),
+///         None => "everything requested".to_string(),
+///     }
+/// }
+///
+/// #[rocket::get("/download")]
+/// fn download(request: RangeRequest) -> String {
+///     describe(request)
+/// }
+///
+/// #[rocket::post("/download")]
+/// fn upload(request: RangeRequest) -> String {
+///     describe(request)
+/// }
+///
+/// let rocket = rocket::build().mount("/", rocket::routes![download, upload]);
+/// let client = Client::tracked(rocket).unwrap();
+/// let range = Header::new("Range", "bytes=0-1");
+/// let response = client.get("/download").header(range.clone()).dispatch();
+/// assert_eq!(response.into_string().unwrap(), "1 ranges requested");
+/// let response = client.post("/download").header(range).dispatch();
+/// assert_eq!(response.into_string().unwrap(), "everything requested");
+/// ```
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for RangeRequest {
+    type Error = Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        if request.method() != Method::Get {
+            return Outcome::Success(RangeRequest::default());
+        }
+        let headers = request.headers();
+        Outcome::Success(RangeRequest::from_fields(
+            headers.get("Range").map(str::as_bytes),
+            headers.get_one("If-Range").map(str::as_bytes),
+            ParseConfig::strict(),
+        ))
+    }
+}
+
+/// Responds from an in-memory body
+///
+/// # Examples
+///
+/// ```rust
+/// use range_header::{RangeRequest, Ranged};
+/// use rocket::{http::Header, local::blocking::Client};
+///
+/// #[rocket::get("/")]
+/// fn index(request: RangeRequest) -> Ranged<&'static str> {
+///     let mut ranged = Ranged::new(request, "hello world");
+///     ranged.representation.content_type = Some("text/plain".to_string());
+///     ranged
+/// }
+///
+/// let client = Client::tracked(rocket::build().mount("/", rocket::routes![index])).unwrap();
+/// let response = client.get("/").dispatch();
+/// assert_eq!(response.status().code, 200);
+/// assert_eq!(response.headers().get_one("Accept-Ranges"), Some("bytes"));
+///
+/// let response = client.get("/").header(Header::new("Range", "bytes=-5")).dispatch();
+/// assert_eq!(response.status().code, 206);
+/// assert_eq!(response.headers().get_one("Content-Range"), Some("bytes 6-10/11"));
+/// assert_eq!(response.into_string().unwrap(), "world");
+///
+/// let response = client.get("/").header(Header::new("Range", "bytes=11-")).dispatch();
+/// assert_eq!(response.status().code, 416);
+/// assert_eq!(response.headers().get_one("Content-Range"), Some("bytes */11"));
+/// ```
+impl<'r, 'o: 'r, B> Responder<'r, 'o> for Ranged<B>
+where
+    B: AsRef<[u8]> + Send + Unpin + 'o,
+{
+    fn respond_to(self, _request: &'r Request<'_>) -> response::Result<'o> {
+        let response = self.response();
+        let mut builder = Response::build();
+        builder.status(Status::new(response.status()));
+        for (name, value) in response.header_fields(&self.representation) {
+            // the length of a sized body is set by Rocket
+            if name != "content-length" {
+                builder.raw_header(name, value);
+            }
+        }
+
+        let body = self.body.as_ref();
+        let body = match &response {
+            RangeResponse::Full { .. } => {
+                let length = body.len();
+                return builder.sized_body(length, Cursor::new(self.body)).ok();
+            }
+            RangeResponse::Single { range, .. } => {
+                body[range.start as usize..=range.end_inclusive as usize].to_vec()
+            }
+            RangeResponse::Multipart(x) => x.render(body),
+            RangeResponse::NotSatisfiable(_) => Vec::new(),
+        };
+        builder.sized_body(body.len(), Cursor::new(body)).ok()
+    }
+}
@@ -0,0 +1,446 @@
+//! Deciding between `200`, `206` and `416` for a request, shared by the framework integrations
+//!
+//! reference: <https://www.rfc-editor.org/rfc/rfc9110#section-14>
+
+use crate::{
+    ByteRange, ContentRange, EntityTag, IfRange, MultipartByteranges, ParseConfig, RangeDecision,
+    RangeHeader, RangePolicy, ResolvedRange, Unsatisfiable,
+};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    io::{self, Read, Seek, SeekFrom},
+    time::SystemTime,
+};
+
+/// `Range` and `If-Range` headers of a request, `ranges` is `None` if the full representation
+/// must be sent anyway
+#[derive(Debug, Eq, PartialEq, Clone, Hash, Default)]
+pub struct RangeRequest {
+    pub ranges: Option<Vec<ByteRange>>,
+    pub if_range: Option<IfRange>,
+}
+
+impl RangeRequest {
+    /// Parses the raw header values as per RFC 9110, see [`ParseConfig::strict`]. A `Range`
+    /// header that is invalid or not in `bytes` is ignored, as is the whole request if
+    /// `If-Range` is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use range_header::{ByteRange, RangeRequest};
+    /// let request = RangeRequest::parse_bytes(Some(b"bytes=0-99"), None);
+    /// assert_eq!(request.ranges, Some(vec![ByteRange::FromToAll(0, 99)]));
+    ///
+    /// let request = RangeRequest::parse_bytes(Some(b"Bytes=0-99,\t-50"), None);
+    /// assert_eq!(
+    ///     request.ranges,
+    ///     Some(vec![ByteRange::FromToAll(0, 99), ByteRange::Last(50)])
+    /// );
+    ///
+    /// let request = RangeRequest::parse_bytes(Some(b"items=0-99"), None);
+    /// assert_eq!(request.ranges, None);
+    ///
+    /// let request = RangeRequest::parse_bytes(Some(b"bytes=0-99"), Some(b"xyzzy"));
+    /// assert_eq!(request, RangeRequest::default());
+    /// ```
+    pub fn parse_bytes(range: Option<&[u8]>, if_range: Option<&[u8]>) -> Self {
+        Self::parse_bytes_with(range, if_range, ParseConfig::strict())
+    }
+
+    /// Like [`RangeRequest::parse_bytes`], parsing `Range` with `config`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use range_header::{ByteRange, ParseConfig, RangeRequest};
+    /// let request =
+    ///     RangeRequest::parse_bytes_with(Some(b"bytes 0 - 99"), None, ParseConfig::lenient());
+    /// assert_eq!(request.ranges, Some(vec![ByteRange::FromToAll(0, 99)]));
+    ///
+    /// let request =
+    ///     RangeRequest::parse_bytes_with(Some(b"bytes=5-1"), None, ParseConfig::lenient());
+    /// assert_eq!(request.ranges, None);
+    /// ```
+    pub fn parse_bytes_with(
+        range: Option<&[u8]>,
+        if_range: Option<&[u8]>,
+        config: ParseConfig,
+    ) -> Self {
+        Self::from_fields(range, if_range, config)
+    }
+
+    /// Like [`RangeRequest::parse_bytes_with`], joining the specs of several `Range` fields
+    pub(crate) fn from_fields<'a, I>(range: I, if_range: Option<&[u8]>, config: ParseConfig) -> Self
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        let ranges = RangeHeader::parse_fields(range, config)
+            .ok()
+            .flatten()
+            .map(|x| x.0);
+        match if_range.map(IfRange::parse_bytes) {
+            None => RangeRequest {
+                ranges,
+                if_range: None,
+            },
+            Some(Ok(if_range)) => RangeRequest {
+                ranges,
+                if_range: Some(if_range),
+            },
+            Some(Err(_)) => RangeRequest::default(),
+        }
+    }
+}
+
+impl From<Option<Vec<ByteRange>>> for RangeRequest {
+    fn from(ranges: Option<Vec<ByteRange>>) -> Self {
+        RangeRequest {
+            ranges,
+            if_range: None,
+        }
+    }
+}
+
+impl From<Option<RangeHeader>> for RangeRequest {
+    fn from(header: Option<RangeHeader>) -> Self {
+        RangeRequest::from(header.map(|x| x.0))
+    }
+}
+
+/// The representation a response is served from
+#[derive(Debug, Eq, PartialEq, Clone, Hash, Default)]
+pub struct Representation {
+    pub length: u64,
+    /// Media type, sent in every part of a `multipart/byteranges` body
+    pub content_type: Option<String>,
+    pub etag: Option<EntityTag>,
+    pub last_modified: Option<SystemTime>,
+}
+
+impl Representation {
+    pub fn new(length: u64) -> Self {
+        Representation {
+            length,
+            ..Representation::default()
+        }
+    }
+}
+
+/// How to respond to a [`RangeRequest`]
+///
+/// # Examples
+///
+/// ```rust
+/// use range_header::{
+///     ContentRange, RangePolicy, RangeRequest, RangeResponse, Representation, ResolvedRange,
+/// };
+/// let representation = Representation::new(1000);
+/// let policy = RangePolicy::default();
+///
+/// let request = RangeRequest::parse_bytes(Some(b"bytes=-100"), None);
+/// let response = RangeResponse::new(&request, &representation, &policy);
+/// assert_eq!(response.status(), 206);
+/// assert_eq!(response.content_length(), 100);
+/// assert_eq!(
+///     response.content_range(),
+///     Some(ContentRange::Bytes {
+///         range: ResolvedRange { start: 900, end_inclusive: 999 },
+///         complete_length: Some(1000),
+///     })
+/// );
+///
+/// let request = RangeRequest::parse_bytes(Some(b"bytes=0-9,20-29"), None);
+/// let response = RangeResponse::new(&request, &representation, &policy);
+/// assert!(matches!(response, RangeResponse::Multipart(_)));
+///
+/// let request = RangeRequest::parse_bytes(Some(b"bytes=1000-"), None);
+/// let response = RangeResponse::new(&request, &representation, &policy);
+/// assert_eq!(response.status(), 416);
+/// assert_eq!(response.content_range(), Some(ContentRange::Unsatisfied(1000)));
+/// ```
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub enum RangeResponse {
+    /// `200 OK` with the full representation
+    Full { complete_length: u64 },
+    /// `206 Partial Content` with a single range
+    Single {
+        range: ResolvedRange,
+        complete_length: u64,
+    },
+    /// `206 Partial Content` with a `multipart/byteranges` body
+    Multipart(MultipartByteranges),
+    /// `416 Range Not Satisfiable`
+    NotSatisfiable(Unsatisfiable),
+}
+
+impl RangeResponse {
+    /// Applies `If-Range` and `policy` to `request`. Parts of a multipart body use the media
+    /// type of `representation`, `application/octet-stream` if it has none.
+    pub fn new(
+        request: &RangeRequest,
+        representation: &Representation,
+        policy: &RangePolicy,
+    ) -> Self {
+        let complete_length = representation.length;
+        let full = RangeResponse::Full { complete_length };
+        let ranges = match &request.ranges {
+            Some(x) => x,
+            None => return full,
+        };
+        if let Some(if_range) = &request.if_range {
+            let etag = representation.etag.as_ref();
+            if !if_range.is_satisfied(etag, representation.last_modified, SystemTime::now()) {
+                return full;
+            }
+        }
+
+        match policy.apply(ranges, complete_length) {
+            RangeDecision::Partial(ranges) => {
+                Self::partial(ranges, representation, &random_boundary())
+            }
+            RangeDecision::Full(_) => full,
+            RangeDecision::NotSatisfiable { unsatisfiable, .. } => {
+                RangeResponse::NotSatisfiable(unsatisfiable)
+            }
+        }
+    }
+
+    fn partial(
+        mut ranges: Vec<ResolvedRange>,
+        representation: &Representation,
+        boundary: &str,
+    ) -> Self {
+        let complete_length = representation.length;
+        if ranges.len() == 1 {
+            return RangeResponse::Single {
+                range: ranges.remove(0),
+                complete_length,
+            };
+        }
+        let content_type = representation
+            .content_type
+            .as_deref()
+            .unwrap_or("application/octet-stream");
+        RangeResponse::Multipart(MultipartByteranges::new(
+            ranges,
+            content_type,
+            complete_length,
+            boundary,
+        ))
+    }
+
+    /// Sorts and merges the parts of a multipart response if they are out of order or overlap,
+    /// as RFC 7233 section 4.1 allows, so that its body can be read in a single pass
+    #[cfg(feature = "tower")]
+    pub(crate) fn into_ascending(self, representation: &Representation) -> Self {
+        let multipart = match &self {
+            RangeResponse::Multipart(x) => x,
+            _ => return self,
+        };
+        let ranges: Vec<ResolvedRange> = multipart.parts().iter().map(|x| x.range).collect();
+        if ranges.windows(2).all(|x| x[0].end_inclusive < x[1].start) {
+            return self;
+        }
+        let ranges = ResolvedRange::coalesce(&ranges, 0)
+            .into_iter()
+            .map(|x| x.range)
+            .collect();
+        Self::partial(ranges, representation, multipart.boundary())
+    }
+
+    /// HTTP status code
+    pub fn status(&self) -> u16 {
+        match self {
+            RangeResponse::Full { .. } => 200,
+            RangeResponse::Single { .. } | RangeResponse::Multipart(_) => 206,
+            RangeResponse::NotSatisfiable(_) => 416,
+        }
+    }
+
+    /// Value of the `Content-Range` header, if any
+    pub fn content_range(&self) -> Option<ContentRange> {
+        match self {
+            RangeResponse::Single {
+                range,
+                complete_length,
+            } => Some(ContentRange::Bytes {
+                range: *range,
+                complete_length: Some(*complete_length),
+            }),
+            RangeResponse::NotSatisfiable(x) => Some(ContentRange::from(*x)),
+            RangeResponse::Full { .. } | RangeResponse::Multipart(_) => None,
+        }
+    }
+
+    /// Exact length of the response body
+    pub fn content_length(&self) -> u64 {
+        match self {
+            RangeResponse::Full { complete_length } => *complete_length,
+            RangeResponse::Single { range, .. } => range.length(),
+            RangeResponse::Multipart(x) => x.content_length(),
+            RangeResponse::NotSatisfiable(_) => 0,
+        }
+    }
+
+    /// Header fields of the response by lowercase name: `accept-ranges`, `content-length` and,
+    /// where they apply, `content-range`, `content-type`, `etag` and `last-modified`
+    #[cfg(any(feature = "actix", feature = "http", feature = "rocket"))]
+    pub(crate) fn header_fields(
+        &self,
+        representation: &Representation,
+    ) -> Vec<(&'static str, String)> {
+        let mut fields = vec![
+            ("accept-ranges", "bytes".to_string()),
+            ("content-length", self.content_length().to_string()),
+        ];
+        if let Some(x) = self.content_range() {
+            fields.push(("content-range", x.to_string()));
+        }
+        if let RangeResponse::NotSatisfiable(_) = self {
+            return fields;
+        }
+
+        let content_type = match self {
+            RangeResponse::Multipart(x) => Some(x.content_type()),
+            _ => representation.content_type.clone(),
+        };
+        fields.extend(content_type.map(|x| ("content-type", x)));
+        fields.extend(
+            representation
+                .etag
+                .as_ref()
+                .map(|x| ("etag", x.to_string())),
+        );
+        fields.extend(
+            representation
+                .last_modified
+                .map(|x| ("last-modified", httpdate::fmt_http_date(x))),
+        );
+        fields
+    }
+
+    /// Pieces of the response body, in order
+    pub(crate) fn segments(&self) -> Vec<Segment> {
+        match self {
+            RangeResponse::Full { complete_length: 0 } | RangeResponse::NotSatisfiable(_) => {
+                Vec::new()
+            }
+            RangeResponse::Full { complete_length } => vec![Segment::Range(ResolvedRange {
+                start: 0,
+                end_inclusive: complete_length - 1,
+            })],
+            RangeResponse::Single { range, .. } => vec![Segment::Range(*range)],
+            RangeResponse::Multipart(multipart) => Segment::multipart(multipart),
+        }
+    }
+}
+
+/// A piece of a response body
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub(crate) enum Segment {
+    /// Part headers and delimiters of a multipart body
+    Static(String),
+    Range(ResolvedRange),
+}
+
+impl Segment {
+    /// Pieces of a `multipart/byteranges` body
+    pub(crate) fn multipart(multipart: &MultipartByteranges) -> Vec<Self> {
+        let mut segments = Vec::new();
+        for part in multipart.parts() {
+            segments.push(Segment::Static(part.header.clone()));
+            segments.push(Segment::Range(part.range));
+        }
+        segments.push(Segment::Static(multipart.closing().to_string()));
+        segments
+    }
+
+    pub(crate) fn length(&self) -> u64 {
+        match self {
+            Segment::Static(x) => x.len() as u64,
+            Segment::Range(x) => x.length(),
+        }
+    }
+}
+
+/// A representation to respond to a [`RangeRequest`] with, the framework integrations turn it
+/// into a `200 OK`, `206 Partial Content`, single or multipart, or `416 Range Not Satisfiable`
+/// response
+///
+/// `body` is in memory or [`Seekable`], `representation.length` must be its length.
+///
+/// # Examples
+///
+/// ```rust
+/// use range_header::{ByteRange, RangeRequest, Ranged};
+/// let request = RangeRequest::from(Some(vec![ByteRange::Last(5)]));
+/// let ranged = Ranged::new(request, "hello world");
+/// assert_eq!(ranged.representation.length, 11);
+/// assert_eq!(ranged.response().status(), 206);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Ranged<B> {
+    pub request: RangeRequest,
+    pub body: B,
+    pub representation: Representation,
+    pub policy: RangePolicy,
+}
+
+impl<B: AsRef<[u8]>> Ranged<B> {
+    /// Serves an in-memory `body` with the default [`RangePolicy`]
+    pub fn new<R: Into<RangeRequest>>(request: R, body: B) -> Self {
+        Ranged {
+            request: request.into(),
+            representation: Representation::new(body.as_ref().len() as u64),
+            body,
+            policy: RangePolicy::default(),
+        }
+    }
+}
+
+impl<R: Read + Seek> Ranged<Seekable<R>> {
+    /// Serves `reader` with the default [`RangePolicy`], its length is the offset of its end
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use range_header::{RangeRequest, Ranged};
+    /// use std::io::Cursor;
+    /// let ranged = Ranged::seekable(RangeRequest::default(), Cursor::new(vec![0; 1000])).unwrap();
+    /// assert_eq!(ranged.representation.length, 1000);
+    /// ```
+    pub fn seekable<T: Into<RangeRequest>>(request: T, mut reader: R) -> io::Result<Self> {
+        let length = reader.seek(SeekFrom::End(0))?;
+        Ok(Ranged {
+            request: request.into(),
+            body: Seekable(reader),
+            representation: Representation::new(length),
+            policy: RangePolicy::default(),
+        })
+    }
+}
+
+impl<B> Ranged<B> {
+    /// Applies `If-Range` and the policy, see [`RangeResponse::new`]
+    pub fn response(&self) -> RangeResponse {
+        RangeResponse::new(&self.request, &self.representation, &self.policy)
+    }
+}
+
+/// A body read on demand, seeking to every range. Reads block the current thread.
+#[derive(Debug, Clone)]
+pub struct Seekable<R>(pub R);
+
+/// A boundary unlikely to occur in any representation
+pub(crate) fn random_boundary() -> String {
+    let random = RandomState::new().build_hasher().finish();
+    format!("{:016x}_RANGE_BOUNDARY", random)
+}
@@ -0,0 +1,117 @@
+//! Streaming the ranges of a tokio `AsyncRead + AsyncSeek` representation
+
+use crate::{
+    async_reader::AsyncRangeReader, reader::CHUNK_SIZE, serve::Segment, MultipartByteranges,
+    RangeResponse, ResolvedRange,
+};
+use alloc::vec::Vec;
+use bytes::Bytes;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_core::Stream;
+use std::io;
+use tokio::io::{AsyncRead, AsyncSeek};
+
+/// Yields exactly the requested bytes of an async seekable representation, such as a
+/// `tokio::fs::File`, in chunks of up to 64 KiB, seeking to the start of every range
+///
+/// Boundary lines and part headers of a multipart body are yielded as separate chunks. The
+/// stream fails with [`io::ErrorKind::UnexpectedEof`] if the representation ends within a range.
+///
+/// # Examples
+///
+/// ```rust
+/// use range_header::{RangeStream, ResolvedRange};
+/// use std::io::Cursor;
+/// let ranges = [
+///     ResolvedRange { start: 6, end_inclusive: 10 },
+///     ResolvedRange { start: 0, end_inclusive: 4 },
+/// ];
+/// let mut stream = RangeStream::new(Cursor::new(b"hello world"), ranges);
+/// stream.set_chunk_size(3);
+/// assert_eq!(stream.remaining(), 10);
+///
+/// let chunks: Vec<_> = futures_executor::block_on_stream(stream)
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+/// assert_eq!(chunks, ["wor", "ld", "hel", "lo"]);
+/// ```
+#[derive(Debug)]
+pub struct RangeStream<R> {
+    reader: AsyncRangeReader<R>,
+}
+
+impl<R> RangeStream<R> {
+    /// Streams `ranges` one after another, without any framing
+    pub fn new<I: IntoIterator<Item = ResolvedRange>>(inner: R, ranges: I) -> Self {
+        Self::from_segments(inner, ranges.into_iter().map(Segment::Range).collect())
+    }
+
+    /// Streams a `multipart/byteranges` body, the parts interleaved with their boundary lines
+    /// and headers
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use range_header::{MultipartByteranges, RangeStream, ResolvedRange};
+    /// use std::io::Cursor;
+    /// let multipart = MultipartByteranges::new(
+    ///     vec![
+    ///         ResolvedRange { start: 0, end_inclusive: 4 },
+    ///         ResolvedRange { start: 6, end_inclusive: 10 },
+    ///     ],
+    ///     "text/plain",
+    ///     11,
+    ///     "THIS_STRING_SEPARATES",
+    /// );
+    /// let stream = RangeStream::multipart(Cursor::new(b"hello world"), &multipart);
+    /// let mut body = Vec::new();
+    /// for chunk in futures_executor::block_on_stream(stream) {
+    ///     body.extend_from_slice(&chunk.unwrap());
+    /// }
+    /// assert_eq!(body, multipart.render(b"hello world"));
+    /// ```
+    pub fn multipart(inner: R, multipart: &MultipartByteranges) -> Self {
+        Self::from_segments(inner, Segment::multipart(multipart))
+    }
+
+    /// Streams the body of `response`, the whole representation for `200 OK` and nothing for
+    /// `416 Range Not Satisfiable`
+    pub fn from_response(inner: R, response: &RangeResponse) -> Self {
+        Self::from_segments(inner, response.segments())
+    }
+
+    fn from_segments(inner: R, segments: Vec<Segment>) -> Self {
+        RangeStream {
+            reader: AsyncRangeReader::new(inner, segments, CHUNK_SIZE),
+        }
+    }
+
+    /// Sets the largest chunk read from the source at once, at least one byte
+    pub fn set_chunk_size(&mut self, chunk_size: usize) {
+        self.reader.set_chunk_size(chunk_size);
+    }
+
+    /// Bytes left to yield
+    pub fn remaining(&self) -> u64 {
+        self.reader.remaining()
+    }
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin> Stream for RangeStream<R> {
+    type Item = io::Result<Bytes>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().reader.poll_chunk(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.remaining() == 0 {
+            (0, Some(0))
+        } else {
+            (1, None)
+        }
+    }
+}
@@ -0,0 +1,377 @@
+//! tower middleware answering `Range` requests from the full responses of any service
+
+use crate::{
+    serve::Segment, EntityTag, ParseConfig, RangePolicy, RangeRequest, RangeResponse,
+    Representation, ResolvedRange,
+};
+use alloc::{
+    collections::VecDeque,
+    string::{String, ToString},
+    vec::Vec,
+};
+use bytes::{Buf, Bytes};
+use core::{
+    fmt,
+    future::Future,
+    mem,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use http::{
+    header::{HeaderName, ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_TYPE, ETAG, LAST_MODIFIED},
+    HeaderMap, Method, Request, Response, StatusCode,
+};
+use http_body::{Body, Frame, SizeHint};
+use pin_project_lite::pin_project;
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Adds `Range` and `If-Range` support to a service
+///
+/// The inner service always produces the full representation. Only `200 OK` responses to `GET`
+/// requests with a known length are turned into `206 Partial Content` or
+/// `416 Range Not Satisfiable`, unless they carry `Accept-Ranges: none`. `Range` headers are
+/// parsed with `parse_config`, RFC 9110 strict by default.
+///
+/// # Examples
+///
+/// ```rust
+/// use http::{header::RANGE, Request, Response};
+/// use http_body_util::{BodyExt, Full};
+/// use range_header::RangeLayer;
+/// use tower::{service_fn, Layer, ServiceExt};
+///
+/// let service = RangeLayer::default().layer(service_fn(|_: Request<()>| async {
+///     let body = Full::new(bytes::Bytes::from("hello world"));
+///     Ok::<_, std::convert::Infallible>(Response::new(body))
+/// }));
+/// let request = Request::builder()
+///     .header(RANGE, "bytes=-5")
+///     .body(())
+///     .unwrap();
+///
+/// let response = futures_executor::block_on(service.oneshot(request)).unwrap();
+/// assert_eq!(response.status(), 206);
+/// assert_eq!(response.headers()["content-range"], "bytes 6-10/11");
+/// let body = futures_executor::block_on(response.into_body().collect()).unwrap();
+/// assert_eq!(body.to_bytes(), "world");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RangeLayer {
+    pub policy: RangePolicy,
+    pub parse_config: ParseConfig,
+}
+
+impl<S> Layer<S> for RangeLayer {
+    type Service = RangeService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RangeService {
+            inner,
+            policy: self.policy.clone(),
+            parse_config: self.parse_config,
+        }
+    }
+}
+
+/// Service created by [`RangeLayer`]
+#[derive(Debug, Clone)]
+pub struct RangeService<S> {
+    inner: S,
+    policy: RangePolicy,
+    parse_config: ParseConfig,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for RangeService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+    ResBody: Body,
+{
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future>;
+    type Response = Response<RangeBody<ResBody>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        let range_request = if request.method() == Method::GET {
+            RangeRequest::from_headers_with(request.headers(), self.parse_config)
+        } else {
+            RangeRequest::default()
+        };
+        ResponseFuture {
+            inner: self.inner.call(request),
+            request: range_request,
+            policy: self.policy.clone(),
+        }
+    }
+}
+
+pin_project! {
+    /// Future of [`RangeService`]
+    pub struct ResponseFuture<F> {
+        #[pin]
+        inner: F,
+        request: RangeRequest,
+        policy: RangePolicy,
+    }
+}
+
+impl<F, B, E> Future for ResponseFuture<F>
+where
+    F: Future<Output = Result<Response<B>, E>>,
+    B: Body,
+{
+    type Output = Result<Response<RangeBody<B>>, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let response = match this.inner.poll(cx) {
+            Poll::Ready(Ok(x)) => x,
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+            Poll::Pending => return Poll::Pending,
+        };
+        Poll::Ready(Ok(respond(response, this.request, this.policy)))
+    }
+}
+
+fn respond<B: Body>(
+    response: Response<B>,
+    request: &RangeRequest,
+    policy: &RangePolicy,
+) -> Response<RangeBody<B>> {
+    let (mut parts, body) = response.into_parts();
+    let accepts_ranges = parts
+        .headers
+        .get(ACCEPT_RANGES)
+        .is_none_or(|x| !x.as_bytes().eq_ignore_ascii_case(b"none"));
+    let length = content_length(&parts.headers).or_else(|| body.size_hint().exact());
+    let length = match length {
+        Some(x) if parts.status == StatusCode::OK && accepts_ranges => x,
+        _ => return Response::from_parts(parts, RangeBody::full(body)),
+    };
+
+    let representation = Representation {
+        length,
+        content_type: header_str(&parts.headers, CONTENT_TYPE),
+        etag: parts
+            .headers
+            .get(ETAG)
+            .and_then(|x| EntityTag::parse_bytes(x.as_bytes()).ok()),
+        last_modified: header_str(&parts.headers, LAST_MODIFIED)
+            .and_then(|x| httpdate::parse_http_date(&x).ok()),
+    };
+    // the inner body is read once, so out of order ranges are served sorted
+    let response =
+        RangeResponse::new(request, &representation, policy).into_ascending(&representation);
+    parts.status = StatusCode::from_u16(response.status()).expect("a valid status code");
+    if let RangeResponse::NotSatisfiable(_) = response {
+        parts.headers.remove(CONTENT_TYPE);
+    }
+    response.write_headers(&representation, &mut parts.headers);
+
+    if let RangeResponse::Full { .. } = response {
+        return Response::from_parts(parts, RangeBody::full(body));
+    }
+    let body = RangeBody::sliced(body, response.segments(), response.content_length());
+    Response::from_parts(parts, body)
+}
+
+fn content_length(headers: &HeaderMap) -> Option<u64> {
+    header_str(headers, CONTENT_LENGTH).and_then(|x| x.parse().ok())
+}
+
+fn header_str(headers: &HeaderMap, name: HeaderName) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|x| x.to_str().ok())
+        .map(|x| x.to_string())
+}
+
+pin_project! {
+    /// Body of a [`RangeService`] response, the inner body either as is or sliced
+    ///
+    /// Slicing streams the inner body, the ranges are sorted and merged beforehand so that
+    /// nothing is buffered. It fails with [`RangeBodyError::TooShort`] if the inner body ends
+    /// before its announced length.
+    pub struct RangeBody<B> {
+        #[pin]
+        inner: B,
+        sliced: Option<Sliced>,
+    }
+}
+
+#[derive(Debug)]
+struct Sliced {
+    /// Ranges in ascending order without overlaps
+    segments: VecDeque<Segment>,
+    /// Last chunk read from the inner body, starting at offset `chunk_start`
+    chunk: Bytes,
+    chunk_start: u64,
+    /// Offset of the next chunk read from the inner body
+    position: u64,
+    /// Whether the inner body returned its last frame
+    ended: bool,
+    remaining: u64,
+}
+
+impl<B> RangeBody<B> {
+    fn full(inner: B) -> Self {
+        RangeBody {
+            inner,
+            sliced: None,
+        }
+    }
+
+    fn sliced(inner: B, segments: Vec<Segment>, content_length: u64) -> Self {
+        RangeBody {
+            inner,
+            sliced: Some(Sliced {
+                segments: segments.into(),
+                chunk: Bytes::new(),
+                chunk_start: 0,
+                position: 0,
+                ended: false,
+                remaining: content_length,
+            }),
+        }
+    }
+}
+
+impl<B> Body for RangeBody<B>
+where
+    B: Body,
+{
+    type Data = Bytes;
+    type Error = RangeBodyError<B::Error>;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let mut this = self.project();
+        let sliced = match this.sliced {
+            Some(x) => x,
+            None => {
+                return this.inner.poll_frame(cx).map(|frame| {
+                    frame.map(|x| {
+                        x.map(|x| x.map_data(|mut x| x.copy_to_bytes(x.remaining())))
+                            .map_err(RangeBodyError::Inner)
+                    })
+                })
+            }
+        };
+
+        loop {
+            let range = match sliced.segments.front_mut() {
+                None => return Poll::Ready(None),
+                Some(Segment::Static(x)) => {
+                    let data = Bytes::from(mem::take(x));
+                    sliced.segments.pop_front();
+                    sliced.remaining -= data.len() as u64;
+                    return Poll::Ready(Some(Ok(Frame::data(data))));
+                }
+                Some(Segment::Range(x)) => *x,
+            };
+
+            let chunk_end = sliced.chunk_start + sliced.chunk.len() as u64;
+            if chunk_end <= range.start {
+                // the range starts after everything read so far
+                if sliced.ended {
+                    // the inner body is shorter than its announced length
+                    sliced.segments.clear();
+                    return Poll::Ready(Some(Err(RangeBodyError::TooShort {
+                        length: sliced.position,
+                    })));
+                }
+                match this.inner.as_mut().poll_frame(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(Some(Err(e))) => {
+                        return Poll::Ready(Some(Err(RangeBodyError::Inner(e))))
+                    }
+                    Poll::Ready(Some(Ok(frame))) => {
+                        if let Ok(mut data) = frame.into_data() {
+                            let data = data.copy_to_bytes(data.remaining());
+                            sliced.chunk_start = sliced.position;
+                            sliced.position += data.len() as u64;
+                            sliced.chunk = data;
+                        }
+                        continue;
+                    }
+                    Poll::Ready(None) => {
+                        sliced.ended = true;
+                        continue;
+                    }
+                }
+            }
+
+            let start = range.start.max(sliced.chunk_start);
+            let end = (range.end_inclusive + 1).min(chunk_end);
+            if start >= end {
+                sliced.segments.pop_front();
+                continue;
+            }
+            if end == range.end_inclusive + 1 {
+                sliced.segments.pop_front();
+            } else {
+                // the rest of the range is in the next chunks
+                sliced.segments[0] = Segment::Range(ResolvedRange {
+                    start: end,
+                    end_inclusive: range.end_inclusive,
+                });
+            }
+            let data = sliced
+                .chunk
+                .slice((start - sliced.chunk_start) as usize..(end - sliced.chunk_start) as usize);
+            sliced.remaining -= data.len() as u64;
+            return Poll::Ready(Some(Ok(Frame::data(data))));
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        match &self.sliced {
+            Some(x) => x.segments.is_empty(),
+            None => self.inner.is_end_stream(),
+        }
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        match &self.sliced {
+            Some(x) => SizeHint::with_exact(x.remaining),
+            None => self.inner.size_hint(),
+        }
+    }
+}
+
+/// Error of a [`RangeBody`]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub enum RangeBodyError<E> {
+    /// The inner body failed
+    Inner(E),
+    /// The inner body ended after `length` bytes, before the end of a requested range
+    TooShort { length: u64 },
+}
+
+impl<E: fmt::Display> fmt::Display for RangeBodyError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RangeBodyError::Inner(e) => e.fmt(f),
+            RangeBodyError::TooShort { length } => write!(
+                f,
+                "body ended after {} bytes, shorter than its announced length",
+                length
+            ),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for RangeBodyError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RangeBodyError::Inner(e) => Some(e),
+            RangeBodyError::TooShort { .. } => None,
+        }
+    }
+}
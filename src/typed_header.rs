@@ -0,0 +1,157 @@
+//! [`headers::Header`] implementations, for `TypedHeader` extractors and
+//! [`headers::HeaderMapExt`]
+
+use crate::{AcceptRanges, ContentRange, IfRange, RangeHeader, RangeUnit};
+use alloc::vec::Vec;
+use core::{convert::TryFrom, iter};
+use headers::{Error, Header, HeaderName, HeaderValue};
+use http::header::{ACCEPT_RANGES, CONTENT_RANGE, IF_RANGE, RANGE};
+
+/// Specs of every `Range` field are joined into one set, parsed as per RFC 9110, see
+/// [`crate::ParseConfig::strict`]
+///
+/// # Examples
+///
+/// ```rust
+/// use headers::HeaderMapExt;
+/// use http::{header::RANGE, HeaderMap, HeaderValue};
+/// use range_header::{ByteRange, RangeHeader};
+/// let mut headers = HeaderMap::new();
+/// headers.typed_insert(RangeHeader(vec![ByteRange::FromTo(100)]));
+/// assert_eq!(headers["range"], "bytes=100-");
+/// assert_eq!(
+///     headers.typed_get::<RangeHeader>(),
+///     Some(RangeHeader(vec![ByteRange::FromTo(100)]))
+/// );
+///
+/// headers.insert(RANGE, HeaderValue::from_static("Bytes=0-1,\t-5"));
+/// assert_eq!(
+///     headers.typed_get::<RangeHeader>(),
+///     Some(RangeHeader(vec![ByteRange::FromToAll(0, 1), ByteRange::Last(5)]))
+/// );
+/// ```
+impl Header for RangeHeader {
+    fn name() -> &'static HeaderName {
+        &RANGE
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        let mut result: Option<Vec<_>> = None;
+        for value in values {
+            let ranges = RangeHeader::try_from(value).map_err(|_| Error::invalid())?;
+            result.get_or_insert_with(Vec::new).extend(ranges.0);
+        }
+        result.map(RangeHeader).ok_or_else(Error::invalid)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(iter::once(HeaderValue::from(self)));
+    }
+}
+
+/// Only the first `Content-Range` field is used
+///
+/// # Examples
+///
+/// ```rust
+/// use headers::HeaderMapExt;
+/// use http::{header::CONTENT_RANGE, HeaderMap, HeaderValue};
+/// use range_header::ContentRange;
+/// let mut headers = HeaderMap::new();
+/// headers.insert(CONTENT_RANGE, HeaderValue::from_static("bytes */1234"));
+/// assert_eq!(headers.typed_get(), Some(ContentRange::Unsatisfied(1234)));
+/// ```
+impl Header for ContentRange {
+    fn name() -> &'static HeaderName {
+        &CONTENT_RANGE
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        values
+            .next()
+            .and_then(|x| ContentRange::try_from(x).ok())
+            .ok_or_else(Error::invalid)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(iter::once(HeaderValue::from(self)));
+    }
+}
+
+/// Units of every `Accept-Ranges` field are joined, like a single comma separated field
+///
+/// Encoding panics if a unit contains bytes not allowed in a header.
+///
+/// # Examples
+///
+/// ```rust
+/// use headers::HeaderMapExt;
+/// use http::{header::ACCEPT_RANGES, HeaderMap, HeaderValue};
+/// use range_header::{AcceptRanges, RangeUnit};
+/// let mut headers = HeaderMap::new();
+/// headers.append(ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+/// headers.append(ACCEPT_RANGES, HeaderValue::from_static("items"));
+/// assert_eq!(
+///     headers.typed_get(),
+///     Some(AcceptRanges::Units(vec![RangeUnit::Bytes, RangeUnit::from("items")]))
+/// );
+/// ```
+impl Header for AcceptRanges {
+    fn name() -> &'static HeaderName {
+        &ACCEPT_RANGES
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        let mut units = Vec::new();
+        for value in values {
+            match AcceptRanges::try_from(value).map_err(|_| Error::invalid())? {
+                AcceptRanges::None => units.push(RangeUnit::from("none")),
+                AcceptRanges::Units(x) => units.extend(x),
+            }
+        }
+        match units.as_slice() {
+            [] => Err(Error::invalid()),
+            [x] if x.as_str() == "none" => Ok(AcceptRanges::None),
+            _ => Ok(AcceptRanges::Units(units)),
+        }
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        let value = HeaderValue::try_from(self).expect("invalid range unit");
+        values.extend(iter::once(value));
+    }
+}
+
+/// Only the first `If-Range` field is used
+///
+/// Encoding panics if an entity-tag contains bytes not allowed in a header.
+///
+/// # Examples
+///
+/// ```rust
+/// use headers::HeaderMapExt;
+/// use http::HeaderMap;
+/// use range_header::{EntityTag, IfRange};
+/// let if_range = IfRange::EntityTag(EntityTag { weak: false, tag: "xyzzy".to_string() });
+/// let mut headers = HeaderMap::new();
+/// headers.typed_insert(if_range.clone());
+/// assert_eq!(headers["if-range"], "\"xyzzy\"");
+/// assert_eq!(headers.typed_get(), Some(if_range));
+/// ```
+impl Header for IfRange {
+    fn name() -> &'static HeaderName {
+        &IF_RANGE
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        values
+            .next()
+            .and_then(|x| IfRange::try_from(x).ok())
+            .ok_or_else(Error::invalid)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        let value = HeaderValue::try_from(self).expect("invalid entity-tag");
+        values.extend(iter::once(value));
+    }
+}
@@ -0,0 +1,51 @@
+//! Streams seekable bodies through actix-web responses
+
+use actix_web::{body::to_bytes, rt::System, test::TestRequest, Responder};
+use range_header::{RangeRequest, Ranged};
+use std::io::{self, Cursor, Read, Seek, SeekFrom};
+
+fn respond<R>(range: &[u8], source: R) -> Result<actix_web::web::Bytes, String>
+where
+    R: Read + Seek + Send + Unpin + 'static,
+{
+    let request = RangeRequest::parse_bytes(Some(range), None);
+    let ranged = Ranged::seekable(request, source).unwrap();
+    let response = ranged.respond_to(&TestRequest::default().to_http_request());
+    System::new()
+        .block_on(to_bytes(response.into_body()))
+        .map_err(|e| e.to_string())
+}
+
+#[test]
+fn body_larger_than_a_chunk() {
+    let content: Vec<u8> = (0..200_000u32).map(|x| x as u8).collect();
+    let body = respond(b"bytes=1000-150000", Cursor::new(content.clone())).unwrap();
+    assert_eq!(body, content[1000..=150_000]);
+
+    let body = respond(b"bytes=-70000", Cursor::new(content.clone())).unwrap();
+    assert_eq!(body, content[130_000..]);
+}
+
+/// Reports a length it does not have
+struct Truncated(Cursor<Vec<u8>>);
+
+impl Read for Truncated {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl Seek for Truncated {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match pos {
+            SeekFrom::End(x) => Ok((100 + x) as u64),
+            pos => self.0.seek(pos),
+        }
+    }
+}
+
+#[test]
+fn source_shorter_than_its_length() {
+    let source = Truncated(Cursor::new(vec![0; 10]));
+    assert!(respond(b"bytes=0-49", source).is_err());
+}
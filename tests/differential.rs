@@ -0,0 +1,223 @@
+//! Checks the hand-written parsers against the pest grammar they replaced
+
+mod oracle;
+
+use range_header::{
+    AcceptRanges, ByteRange, ContentRange, EntityTag, Range, RangeHeader, RangeParseError, RangeSet,
+};
+use std::fmt::{Debug, Display};
+
+const TOKENS: &[&str] = &[
+    "bytes=",
+    "bytes",
+    "bytes ",
+    "items=",
+    "Bytes",
+    "none",
+    "NONE",
+    "=",
+    " ",
+    "\t",
+    ",",
+    "-",
+    "/",
+    "*",
+    "*/",
+    "0",
+    "7",
+    "10",
+    "0042",
+    "18446744073709551615",
+    "18446744073709551616",
+    "x",
+    "\"",
+    "W/",
+    "é",
+    "!",
+];
+
+/// Syntax error offsets are where each parser gave up, which is not part of the contract
+fn same_error(a: &RangeParseError, b: &RangeParseError) -> bool {
+    match (a, b) {
+        (RangeParseError::Syntax { .. }, RangeParseError::Syntax { .. }) => true,
+        _ => a == b,
+    }
+}
+
+fn check<T: Debug + PartialEq>(
+    name: &str,
+    input: &str,
+    actual: Result<T, RangeParseError>,
+    expected: Result<T, RangeParseError>,
+) {
+    let same = match (&actual, &expected) {
+        (Ok(a), Ok(b)) => a == b,
+        (Err(a), Err(b)) => same_error(a, b),
+        _ => false,
+    };
+    assert!(
+        same,
+        "{} {:?}: got {:?}, expected {:?}",
+        name, input, actual, expected
+    );
+}
+
+fn check_all(input: &str) {
+    check(
+        "ByteRange::try_parse",
+        input,
+        ByteRange::try_parse(input),
+        oracle::byte_ranges(input),
+    );
+    let lenient = ByteRange::parse(input);
+    assert!(oracle::byte_ranges(input).map_or(true, |x| x == lenient));
+
+    for unit in &["bytes=", "items="] {
+        let header = format!("{}{}", unit, input);
+        let expected = oracle::range(&header).and_then(|(unit, set)| {
+            if unit.eq_ignore_ascii_case("bytes") {
+                Ok((
+                    "bytes".to_string(),
+                    RangeSet::Ranges(oracle::int_ranges(&set)?),
+                ))
+            } else {
+                Ok((unit, RangeSet::Other(set)))
+            }
+        });
+        let actual = Range::parse(&header).map(|x| (x.unit.to_string(), x.set));
+        check("Range::parse", &header, actual, expected);
+        round_trip(&header, str::parse::<RangeHeader>);
+    }
+
+    // the grammar only knows a lowercase unit
+    let lowercase_unit = match input.get(..6) {
+        Some(unit) if unit.eq_ignore_ascii_case("bytes ") => format!("bytes {}", &input[6..]),
+        _ => input.to_string(),
+    };
+    let expected = oracle::content_range(&lowercase_unit);
+    // the grammar allows a range ending at `u64::MAX`, whose length overflows
+    let overflows = matches!(
+        expected,
+        Ok(ContentRange::Bytes { range, .. }) if range.end_inclusive == u64::MAX
+    );
+    if !overflows {
+        check(
+            "ContentRange::parse",
+            input,
+            ContentRange::parse(input),
+            expected,
+        );
+    }
+    round_trip(input, str::parse::<RangeHeader>);
+    round_trip(input, ContentRange::parse);
+    check(
+        "EntityTag::parse",
+        input,
+        EntityTag::parse(input),
+        oracle::entity_tag(input),
+    );
+    // the grammar only knows spaces as whitespace
+    check(
+        "AcceptRanges::parse",
+        input,
+        AcceptRanges::parse(input),
+        oracle::accept_ranges(&input.replace('\t', " ")),
+    );
+}
+
+/// Formatting a parsed header must give something parsing back to the same value
+fn round_trip<T>(input: &str, parse: fn(&str) -> Result<T, RangeParseError>)
+where
+    T: Display + Debug + PartialEq,
+{
+    if let Ok(parsed) = parse(input) {
+        let formatted = parsed.to_string();
+        assert_eq!(
+            parse(&formatted),
+            Ok(parsed),
+            "{:?} formatted as {:?}",
+            input,
+            formatted
+        );
+    }
+}
+
+/// xorshift, good enough to pick tokens
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self, bound: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % bound as u64) as usize
+    }
+}
+
+#[test]
+fn short_token_sequences() {
+    check_all("");
+    for a in TOKENS {
+        check_all(a);
+        for b in TOKENS {
+            check_all(&format!("{}{}", a, b));
+            for c in TOKENS {
+                check_all(&format!("{}{}{}", a, b, c));
+            }
+        }
+    }
+}
+
+#[test]
+fn random_token_sequences() {
+    let mut random = Random(0x2545_f491_4f6c_dd1d);
+    for _ in 0..50_000 {
+        let prefix = ["bytes=", "bytes ", "W/\"", "\"", ""][random.next(5)];
+        let len = random.next(12);
+        let mut input = prefix.to_string();
+        for _ in 0..len {
+            input.push_str(TOKENS[random.next(TOKENS.len())]);
+        }
+        check_all(&input);
+    }
+}
+
+#[test]
+fn known_headers() {
+    let headers = [
+        "bytes=0-499",
+        "bytes=500-999",
+        "bytes=-500",
+        "bytes=9500-",
+        "bytes=0-0,-1",
+        "bytes=500-600,601-999",
+        "bytes=500-700,601-999",
+        " bytes= 10 - 20 , , 30- ,",
+        "bytes=,0-1",
+        "bytes=0-1,,",
+        "bytes=1-2-3",
+        "bytes=0-184467440737095516150",
+        "bytes=184467440737095516150-",
+        "bytes=-184467440737095516150",
+        "bytes=000000000000000000000000001-2",
+        "bytes=184467440737095516160-184467440737095516150",
+        "bytes=184467440737095516150-184467440737095516160",
+        "bytes 0-499/1234",
+        "bytes 0-499/*",
+        "bytes */1234",
+        "bytes 0-1234/1234",
+        "bytes 10-1/1234",
+        "items 0-24/66",
+        "\"xyzzy\"",
+        "W/\"xyzzy\"",
+        "\"\"",
+        "\"a\"b\"",
+        "none",
+        "bytes, items",
+        " , ,bytes ,",
+        "bytes items",
+    ];
+    for header in &headers {
+        check_all(header);
+    }
+}
@@ -0,0 +1,147 @@
+//! Feeds `multipart/byteranges` bodies to `MultipartParser` in awkward pieces
+
+use range_header::{
+    ByteRange, ContentRange, MultipartError, MultipartEvent, MultipartParser, RangeParseError,
+    ResolvedRange,
+};
+
+const BODY: &[u8] = b"--SEP\r\n\
+Content-Type: text/plain\r\n\
+Content-Range: bytes 0-3/20\r\n\
+\r\n\
+0123\r\n\
+--SEP\r\n\
+Content-Range: bytes 10-14/20\r\n\
+\r\n\
+--SE-\r\n\
+--SEP--\r\n";
+
+fn parser() -> MultipartParser {
+    MultipartParser::from_content_type("multipart/byteranges; boundary=SEP").unwrap()
+}
+
+/// Feeds `chunks` one after another, merging adjacent data events
+fn parse(
+    mut parser: MultipartParser,
+    chunks: &[&[u8]],
+) -> Result<Vec<MultipartEvent>, MultipartError> {
+    let mut events = Vec::new();
+    for chunk in chunks {
+        parser.feed(chunk);
+        while let Some(event) = parser.next_event()? {
+            match (events.last_mut(), event) {
+                (Some(MultipartEvent::Data(data)), MultipartEvent::Data(more)) => {
+                    data.extend_from_slice(&more)
+                }
+                (_, event) => events.push(event),
+            }
+        }
+    }
+    parser.finish()?;
+    Ok(events)
+}
+
+fn part_start(start: u64, end_inclusive: u64, content_type: Option<&str>) -> MultipartEvent {
+    MultipartEvent::PartStart {
+        content_range: ContentRange::Bytes {
+            range: ResolvedRange {
+                start,
+                end_inclusive,
+            },
+            complete_length: Some(20),
+        },
+        content_type: content_type.map(ToString::to_string),
+    }
+}
+
+fn expected_events() -> Vec<MultipartEvent> {
+    vec![
+        part_start(0, 3, Some("text/plain")),
+        MultipartEvent::Data(b"0123".to_vec()),
+        MultipartEvent::PartEnd,
+        part_start(10, 14, None),
+        MultipartEvent::Data(b"--SE-".to_vec()),
+        MultipartEvent::PartEnd,
+    ]
+}
+
+#[test]
+fn whole_body() {
+    assert_eq!(parse(parser(), &[BODY]), Ok(expected_events()));
+}
+
+#[test]
+fn split_at_every_offset() {
+    for at in 0..=BODY.len() {
+        let (a, b) = BODY.split_at(at);
+        assert_eq!(
+            parse(parser(), &[a, b]),
+            Ok(expected_events()),
+            "split at {}",
+            at
+        );
+    }
+}
+
+#[test]
+fn one_byte_at_a_time() {
+    let chunks: Vec<&[u8]> = BODY.chunks(1).collect();
+    assert_eq!(parse(parser(), &chunks), Ok(expected_events()));
+}
+
+#[test]
+fn lf_line_endings() {
+    let body: Vec<u8> = BODY.iter().copied().filter(|x| *x != b'\r').collect();
+    assert_eq!(parse(parser(), &[&body]), Ok(expected_events()));
+
+    let chunks: Vec<&[u8]> = body.chunks(1).collect();
+    assert_eq!(parse(parser(), &chunks), Ok(expected_events()));
+}
+
+#[test]
+fn requested_ranges() {
+    let mut requested = parser();
+    requested.expect(ByteRange::parse("bytes=0-3,10-"));
+    assert_eq!(parse(requested, &[BODY]), Ok(expected_events()));
+
+    let mut unrequested = parser();
+    unrequested.expect(ByteRange::parse("bytes=0-3"));
+    assert_eq!(
+        parse(unrequested, &[BODY]),
+        Err(MultipartError::UnrequestedRange(ResolvedRange {
+            start: 10,
+            end_inclusive: 14
+        }))
+    );
+}
+
+#[test]
+fn length_mismatch() {
+    let body = b"--SEP\r\nContent-Range: bytes 0-3/20\r\n\r\n012\r\n--SEP--\r\n";
+    assert_eq!(
+        parse(parser(), &[body]),
+        Err(MultipartError::LengthMismatch {
+            expected: 4,
+            actual: 3
+        })
+    );
+}
+
+#[test]
+fn truncated_body() {
+    assert_eq!(
+        parse(parser(), &[&BODY[..BODY.len() - 9]]),
+        Err(MultipartError::UnexpectedEof)
+    );
+}
+
+#[test]
+fn range_ending_at_u64_max() {
+    let body = b"--SEP\r\nContent-Range: bytes 0-18446744073709551615/*\r\n\r\n";
+    assert_eq!(
+        parse(parser(), &[body]),
+        Err(MultipartError::InvalidContentRange(
+            RangeParseError::Overflow { offset: 8 }
+        ))
+    );
+}
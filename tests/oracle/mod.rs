@@ -0,0 +1,205 @@
+//! The pest based parsers the hand-written ones replaced, kept as a reference implementation
+
+#![allow(dead_code)]
+
+use pest::{
+    error::InputLocation,
+    iterators::{Pair, Pairs},
+    Parser,
+};
+use pest_derive::Parser;
+use range_header::{
+    AcceptRanges, ByteRange, ContentRange, EntityTag, RangeParseError, RangeUnit, ResolvedRange,
+};
+
+#[derive(Parser)]
+#[grammar = "../tests/byte_range.pest"]
+struct ByteRangeParser;
+
+pub fn byte_ranges(header: &str) -> Result<Vec<ByteRange>, RangeParseError> {
+    match ByteRangeParser::parse(Rule::byte_ranges_specifier, header) {
+        Err(e) => Err(syntax_error(header, e, Rule::range_unit_prefix)),
+        Ok(x) => from_pairs(x.peek().unwrap().into_inner()),
+    }
+}
+
+pub fn int_ranges(set: &str) -> Result<Vec<ByteRange>, RangeParseError> {
+    match ByteRangeParser::parse(Rule::int_range_set, set) {
+        Err(e) => Err(RangeParseError::Syntax {
+            offset: error_offset(&e),
+        }),
+        Ok(x) => from_pairs(x.peek().unwrap().into_inner()),
+    }
+}
+
+/// Unit and raw range set of a `Range` header of any unit
+pub fn range(header: &str) -> Result<(String, String), RangeParseError> {
+    match ByteRangeParser::parse(Rule::range, header) {
+        Err(e) => Err(RangeParseError::Syntax {
+            offset: error_offset(&e),
+        }),
+        Ok(x) => {
+            let mut inner_pairs = x.peek().unwrap().into_inner();
+            let unit = inner_pairs.next().unwrap().as_str().to_string();
+            let set = inner_pairs.next().unwrap().as_str().to_string();
+            Ok((unit, set))
+        }
+    }
+}
+
+pub fn content_range(header: &str) -> Result<ContentRange, RangeParseError> {
+    let resp = match ByteRangeParser::parse(Rule::content_range, header) {
+        Err(e) => return Err(syntax_error(header, e, Rule::content_range_unit_prefix)),
+        Ok(x) => x.peek().unwrap().into_inner().next().unwrap(),
+    };
+
+    match resp.as_rule() {
+        Rule::byte_range_resp => {
+            let mut inner_pairs = resp.into_inner();
+            let start = parse_digit(inner_pairs.next().unwrap())?;
+            let end_inclusive = parse_digit(inner_pairs.next().unwrap())?;
+            let complete_length = match inner_pairs.next() {
+                Some(x) => Some(parse_digit(x)?),
+                None => None,
+            };
+
+            if start > end_inclusive {
+                return Err(RangeParseError::InvertedRange {
+                    first: start,
+                    last: end_inclusive,
+                });
+            }
+            if let Some(complete_length) = complete_length {
+                if complete_length <= end_inclusive {
+                    return Err(RangeParseError::OutOfBounds {
+                        last: end_inclusive,
+                        complete_length,
+                    });
+                }
+            }
+
+            Ok(ContentRange::Bytes {
+                range: ResolvedRange {
+                    start,
+                    end_inclusive,
+                },
+                complete_length,
+            })
+        }
+        Rule::unsatisfied_range => {
+            let complete_length = parse_digit(resp.into_inner().next().unwrap())?;
+            Ok(ContentRange::Unsatisfied(complete_length))
+        }
+        _ => unreachable!(),
+    }
+}
+
+pub fn entity_tag(header: &str) -> Result<EntityTag, RangeParseError> {
+    let inner_pairs = match ByteRangeParser::parse(Rule::entity_tag, header) {
+        Err(e) => {
+            return Err(RangeParseError::Syntax {
+                offset: error_offset(&e),
+            })
+        }
+        Ok(x) => x.peek().unwrap().into_inner(),
+    };
+
+    let mut result = EntityTag {
+        weak: false,
+        tag: String::new(),
+    };
+    for pair in inner_pairs {
+        match pair.as_rule() {
+            Rule::weak => result.weak = true,
+            Rule::opaque_tag => result.tag = pair.as_str().to_string(),
+            Rule::EOI => {}
+            _ => unreachable!(),
+        }
+    }
+    Ok(result)
+}
+
+pub fn accept_ranges(header: &str) -> Result<AcceptRanges, RangeParseError> {
+    let pairs = match ByteRangeParser::parse(Rule::accept_ranges, header) {
+        Err(e) => {
+            return Err(RangeParseError::Syntax {
+                offset: error_offset(&e),
+            })
+        }
+        Ok(x) => x.peek().unwrap().into_inner(),
+    };
+
+    let units: Vec<RangeUnit> = pairs
+        .filter(|x| x.as_rule() == Rule::range_unit)
+        .map(|x| RangeUnit::from(x.as_str()))
+        .collect();
+    if units.len() == 1 && units[0].as_str().eq_ignore_ascii_case("none") {
+        return Ok(AcceptRanges::None);
+    }
+    Ok(AcceptRanges::Units(units))
+}
+
+fn from_pairs(pairs: Pairs<Rule>) -> Result<Vec<ByteRange>, RangeParseError> {
+    let mut result = Vec::new();
+    for spec in pairs {
+        let range = match spec.as_rule() {
+            Rule::from_to => ByteRange::FromTo(parse_position(spec.into_inner().next().unwrap())),
+            Rule::from_to_all => {
+                let mut inner_pairs = spec.into_inner();
+                let begin = inner_pairs.next().unwrap();
+                let end = inner_pairs.next().unwrap();
+                if exact_digits(&begin) > exact_digits(&end) {
+                    return Err(RangeParseError::InvertedRange {
+                        first: parse_position(begin),
+                        last: parse_position(end),
+                    });
+                }
+                ByteRange::FromToAll(parse_position(begin), parse_position(end))
+            }
+            Rule::last => ByteRange::Last(parse_position(spec.into_inner().next().unwrap())),
+            Rule::EOI => continue,
+            _ => unreachable!(),
+        };
+        result.push(range);
+    }
+    Ok(result)
+}
+
+fn syntax_error(header: &str, e: pest::error::Error<Rule>, prefix_rule: Rule) -> RangeParseError {
+    if let Ok(mut pairs) = ByteRangeParser::parse(prefix_rule, header) {
+        let unit = pairs.next().unwrap().into_inner().next().unwrap().as_str();
+        if unit != "bytes" {
+            return RangeParseError::UnsupportedUnit(unit.to_string());
+        }
+    }
+    RangeParseError::Syntax {
+        offset: error_offset(&e),
+    }
+}
+
+fn error_offset(e: &pest::error::Error<Rule>) -> usize {
+    match e.location {
+        InputLocation::Pos(x) => x,
+        InputLocation::Span((x, _)) => x,
+    }
+}
+
+/// Positions of a `Range` header saturate instead of overflowing
+fn parse_position(digit: Pair<Rule>) -> u64 {
+    digit.as_str().parse().unwrap_or(u64::MAX)
+}
+
+/// Digits without leading zeros, ordered by length first so they compare like the numbers
+fn exact_digits<'a>(digit: &Pair<'a, Rule>) -> (usize, &'a str) {
+    let digits = digit.as_str().trim_start_matches('0');
+    (digits.len(), digits)
+}
+
+fn parse_digit(digit: Pair<Rule>) -> Result<u64, RangeParseError> {
+    digit
+        .as_str()
+        .parse()
+        .map_err(|_| RangeParseError::Overflow {
+            offset: digit.as_span().start(),
+        })
+}
@@ -0,0 +1,273 @@
+//! Serves the responses of a fake service through `RangeLayer`
+
+use bytes::Bytes;
+use http::{
+    header::{ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_TYPE, RANGE},
+    Method, Request, Response, StatusCode,
+};
+use http_body::{Body, Frame};
+use http_body_util::BodyExt;
+use range_header::{
+    ByteRange, ContentRange, MultipartEvent, MultipartParser, ParseConfig, RangeBody,
+    RangeBodyError, RangeLayer,
+};
+use std::{
+    collections::VecDeque,
+    convert::Infallible,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tower::{service_fn, Layer, ServiceExt};
+
+const CONTENT: &str = "hello world";
+
+/// A body sent in several frames, without any size hint
+struct Chunked(VecDeque<Bytes>);
+
+impl Chunked {
+    fn new(chunks: &[&'static str]) -> Self {
+        Chunked(
+            chunks
+                .iter()
+                .map(|x| Bytes::from_static(x.as_bytes()))
+                .collect(),
+        )
+    }
+}
+
+impl Body for Chunked {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, Infallible>>> {
+        Poll::Ready(self.0.pop_front().map(|x| Ok(Frame::data(x))))
+    }
+}
+
+/// Sends `response` through a `RangeLayer` for a `method` request with `range`
+fn serve(
+    method: Method,
+    range: &str,
+    response: impl Fn() -> Response<Chunked> + Clone,
+) -> Response<RangeBody<Chunked>> {
+    serve_with(RangeLayer::default(), method, range, response)
+}
+
+fn serve_with(
+    layer: RangeLayer,
+    method: Method,
+    range: &str,
+    response: impl Fn() -> Response<Chunked> + Clone,
+) -> Response<RangeBody<Chunked>> {
+    let service = layer.layer(service_fn(move |_: Request<()>| {
+        let response = response.clone();
+        async move { Ok::<_, Infallible>(response()) }
+    }));
+    let request = Request::builder()
+        .method(method)
+        .header(RANGE, range)
+        .body(())
+        .unwrap();
+    futures_executor::block_on(service.oneshot(request)).unwrap()
+}
+
+/// `CONTENT` in `chunks` with its `Content-Length`
+fn get(range: &str, chunks: &'static [&'static str]) -> Response<RangeBody<Chunked>> {
+    serve(Method::GET, range, move || {
+        Response::builder()
+            .header(CONTENT_LENGTH, CONTENT.len())
+            .body(Chunked::new(chunks))
+            .unwrap()
+    })
+}
+
+fn collect(response: Response<RangeBody<Chunked>>) -> Result<Bytes, RangeBodyError<Infallible>> {
+    futures_executor::block_on(response.into_body().collect()).map(|x| x.to_bytes())
+}
+
+/// Ranges and data of a `multipart/byteranges` response, in body order
+fn parts(response: Response<RangeBody<Chunked>>) -> Vec<(u64, Vec<u8>)> {
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    let content_type = response.headers()[CONTENT_TYPE].to_str().unwrap();
+    let mut parser = MultipartParser::from_content_type(content_type).unwrap();
+    let content_length: usize = response.headers()[CONTENT_LENGTH]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    let body = collect(response).unwrap();
+    assert_eq!(body.len(), content_length);
+
+    parser.feed(&body);
+    let mut parts = Vec::new();
+    while let Some(event) = parser.next_event().unwrap() {
+        match event {
+            MultipartEvent::PartStart { content_range, .. } => match content_range {
+                ContentRange::Bytes { range, .. } => parts.push((range.start, Vec::new())),
+                x => panic!("unexpected {:?}", x),
+            },
+            MultipartEvent::Data(data) => parts.last_mut().unwrap().1.extend(data),
+            MultipartEvent::PartEnd => {}
+        }
+    }
+    parser.finish().unwrap();
+    parts
+}
+
+const CHUNKS: &[&str] = &["he", "llo w", "o", "rld"];
+
+#[test]
+fn single_range_across_chunks() {
+    for range in &[
+        "bytes=1-8",
+        "bytes=2-6",
+        "bytes=-4",
+        "bytes=0-",
+        "bytes=4-4",
+    ] {
+        let response = get(range, CHUNKS);
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        let resolved = ByteRange::parse(range)[0].resolve(11).unwrap();
+        let expected = &CONTENT[resolved.start as usize..=resolved.end_inclusive as usize];
+        assert_eq!(
+            response.headers()[CONTENT_LENGTH],
+            expected.len().to_string()
+        );
+        assert_eq!(collect(response).unwrap(), expected, "{}", range);
+    }
+}
+
+#[test]
+fn ascending_ranges_across_chunks() {
+    let response = get("bytes=0-2,4-7,9-", CHUNKS);
+    assert_eq!(
+        parts(response),
+        vec![
+            (0, b"hel".to_vec()),
+            (4, b"o wo".to_vec()),
+            (9, b"ld".to_vec()),
+        ]
+    );
+}
+
+#[test]
+fn non_ascending_ranges_are_sorted() {
+    let response = get("bytes=6-10,0-4", CHUNKS);
+    assert_eq!(
+        parts(response),
+        vec![(0, b"hello".to_vec()), (6, b"world".to_vec())]
+    );
+
+    let response = get("bytes=9-,4-4,0-1", CHUNKS);
+    assert_eq!(
+        parts(response),
+        vec![(0, b"he".to_vec()), (4, b"o".to_vec()), (9, b"ld".to_vec())]
+    );
+}
+
+#[test]
+fn overlapping_ranges_are_merged() {
+    let response = get("bytes=0-6,3-9", CHUNKS);
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(response.headers()["content-range"], "bytes 0-9/11");
+    assert_eq!(collect(response).unwrap(), "hello worl");
+
+    let response = get("bytes=6-8,0-1,7-10", CHUNKS);
+    assert_eq!(
+        parts(response),
+        vec![(0, b"he".to_vec()), (6, b"world".to_vec())]
+    );
+}
+
+#[test]
+fn body_shorter_than_content_length() {
+    const SHORT: &[&str] = &["hello", " wo"];
+    assert_eq!(
+        collect(get("bytes=6-10", SHORT)),
+        Err(RangeBodyError::TooShort { length: 8 })
+    );
+    assert_eq!(
+        collect(get("bytes=9-10,0-1", SHORT)),
+        Err(RangeBodyError::TooShort { length: 8 })
+    );
+    assert_eq!(collect(get("bytes=0-4", SHORT)).unwrap(), "hello");
+}
+
+#[test]
+fn passes_through_non_ok_responses() {
+    let response = serve(Method::GET, "bytes=0-1", || {
+        Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .header(CONTENT_LENGTH, CONTENT.len())
+            .body(Chunked::new(CHUNKS))
+            .unwrap()
+    });
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(collect(response).unwrap(), CONTENT);
+}
+
+#[test]
+fn passes_through_accept_ranges_none() {
+    let response = serve(Method::GET, "bytes=0-1", || {
+        Response::builder()
+            .header(CONTENT_LENGTH, CONTENT.len())
+            .header(ACCEPT_RANGES, "None")
+            .body(Chunked::new(CHUNKS))
+            .unwrap()
+    });
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(collect(response).unwrap(), CONTENT);
+}
+
+#[test]
+fn passes_through_unknown_length() {
+    let response = serve(Method::GET, "bytes=0-1", || {
+        Response::new(Chunked::new(CHUNKS))
+    });
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get(ACCEPT_RANGES).is_none());
+    assert_eq!(collect(response).unwrap(), CONTENT);
+}
+
+#[test]
+fn ignores_range_of_non_get_requests() {
+    let response = serve(Method::POST, "bytes=0-1", || {
+        Response::builder()
+            .header(CONTENT_LENGTH, CONTENT.len())
+            .body(Chunked::new(CHUNKS))
+            .unwrap()
+    });
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(collect(response).unwrap(), CONTENT);
+}
+
+#[test]
+fn parses_range_as_per_rfc_9110() {
+    let response = get("Bytes=0-1,\t6-7", CHUNKS);
+    assert_eq!(
+        parts(response),
+        vec![(0, b"he".to_vec()), (6, b"wo".to_vec())]
+    );
+
+    let response = get("bytes=0 - 1", CHUNKS);
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[test]
+fn lenient_parsing_of_only_inverted_specs() {
+    let layer = RangeLayer {
+        parse_config: ParseConfig::lenient(),
+        ..RangeLayer::default()
+    };
+    let response = serve_with(layer, Method::GET, "bytes=5-1", || {
+        Response::builder()
+            .header(CONTENT_LENGTH, CONTENT.len())
+            .body(Chunked::new(CHUNKS))
+            .unwrap()
+    });
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(collect(response).unwrap(), CONTENT);
+}